@@ -0,0 +1,58 @@
+use gagerr_core::gage_study_data::GageStudyData;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// A line of the `Part,Operator,Trial,Value` CSV format the web app reads.
+/// Any other columns are ignored.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Record {
+    part: String,
+    operator: u32,
+    trial: u32,
+    value: f64,
+}
+
+/// Why a CSV file could not be read.
+#[derive(Debug)]
+pub enum InputError {
+    Csv(csv::Error),
+    MissingColumn(&'static str),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv(e) => write!(f, "{}", e),
+            Self::MissingColumn(column) => write!(f, "missing column {}", column),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<csv::Error> for InputError {
+    fn from(e: csv::Error) -> Self {
+        Self::Csv(e)
+    }
+}
+
+/// Reads the measurements of a crossed study. The first unreadable line
+/// fails the whole file.
+pub fn read_measurements(path: &Path) -> Result<Vec<GageStudyData>, InputError> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    for column in ["Part", "Operator", "Trial", "Value"] {
+        if !headers.iter().any(|h| h == column) {
+            return Err(InputError::MissingColumn(column));
+        }
+    }
+    reader
+        .deserialize()
+        .map(|record| {
+            let r: Record = record?;
+            Ok(GageStudyData::new(&r.part, r.operator, r.trial, r.value))
+        })
+        .collect()
+}
@@ -0,0 +1,157 @@
+//! Crossed Gage R&R of CSV files from the command line, for test stands
+//! and release pipelines without a browser.
+
+mod input;
+mod output;
+mod report;
+
+use clap::{Parser, ValueEnum};
+use gagerr_core::gage_study_data_set::GageStudyDataSet;
+use gagerr_core::spec_limits::SpecLimits;
+use gagerr_core::study_type::VarCompMethod;
+use report::{Limits, Report};
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Method {
+    Anova,
+    Reml,
+}
+
+impl From<Method> for VarCompMethod {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Anova => VarCompMethod::Anova,
+            Method::Reml => VarCompMethod::Reml,
+        }
+    }
+}
+
+/// Crossed Gage R&R of CSV files with Part, Operator, Trial and Value
+/// columns, one study per file.
+#[derive(Parser)]
+#[command(name = "gagerr", version)]
+#[command(
+    after_help = "Exit status is 0 when every study is accepted, 1 when a study fails \
+                  one of the limits and 2 when a file cannot be read or analysed."
+)]
+struct Args {
+    /// CSV files to analyse
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Variance component estimation, REML is always used for unbalanced data
+    #[arg(long, value_enum, default_value_t = Method::Anova)]
+    method: Method,
+    /// Lower spec limit
+    #[arg(long, allow_negative_numbers = true)]
+    lsl: Option<f64>,
+    /// Upper spec limit
+    #[arg(long, allow_negative_numbers = true)]
+    usl: Option<f64>,
+    /// Tolerance width, used when both spec limits are not given
+    #[arg(long)]
+    tolerance: Option<f64>,
+    /// Historical process standard deviation, for the % Process column
+    #[arg(long)]
+    historical_stddev: Option<f64>,
+    /// Keep the part/operator interaction in the model however small it is
+    #[arg(long)]
+    no_pool: bool,
+    /// p-value of the interaction above which it is pooled into repeatability
+    #[arg(long, default_value_t = 0.25, value_parser = parse_alpha)]
+    alpha_to_remove: f64,
+    /// Confidence level of the variance component intervals
+    #[arg(long, default_value_t = 0.95, value_parser = parse_confidence)]
+    confidence: f64,
+    /// Highest % Study Var of the total Gage R&R that is accepted
+    #[arg(long, default_value_t = 30.0)]
+    max_study_var: f64,
+    /// Highest % Tolerance of the total Gage R&R that is accepted
+    #[arg(long)]
+    max_tolerance: Option<f64>,
+    /// Lowest number of distinct categories that is accepted
+    #[arg(long)]
+    min_ndc: Option<u32>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let limits = Limits {
+        max_study_var: args.max_study_var,
+        max_tolerance: args.max_tolerance,
+        min_ndc: args.min_ndc,
+    };
+
+    let mut reports = Vec::new();
+    let mut failed = false;
+    for path in args.files.iter() {
+        match analyse(path, &args) {
+            Ok(gagerr) => reports.push(Report::new(path.display().to_string(), &gagerr, &limits)),
+            Err(e) => {
+                eprintln!("gagerr: {}: {}", path.display(), e);
+                failed = true;
+            }
+        }
+    }
+
+    let mut out = io::stdout().lock();
+    let written = match args.format {
+        Format::Text => output::text(&reports, &mut out),
+        Format::Json => output::json(&reports, &mut out),
+        Format::Csv => output::csv(&reports, &mut out),
+    }
+    .and_then(|_| out.flush());
+    if let Err(e) = written {
+        eprintln!("gagerr: {}", e);
+        failed = true;
+    }
+
+    if failed {
+        ExitCode::from(2)
+    } else if reports.iter().all(|r| r.verdict.accepted()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+fn analyse(path: &Path, args: &Args) -> Result<GageStudyDataSet, Box<dyn Error>> {
+    let mut gagerr = GageStudyDataSet::new_with_data(input::read_measurements(path)?);
+    gagerr.spec_limits = SpecLimits {
+        lsl: args.lsl,
+        usl: args.usl,
+        width: args.tolerance,
+    };
+    gagerr.historical_stddev = args.historical_stddev.filter(|h| *h > 0.0);
+    gagerr.confidence = args.confidence;
+    gagerr.varcomp_method = args.method.into();
+    gagerr.pool_interaction = !args.no_pool;
+    gagerr.alpha_to_remove = args.alpha_to_remove;
+    Ok(gagerr.calculate()?)
+}
+
+fn parse_alpha(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(alpha) if (0.0..=1.0).contains(&alpha) => Ok(alpha),
+        _ => Err(format!("{value} is not a probability between 0 and 1")),
+    }
+}
+
+fn parse_confidence(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(confidence) if confidence > 0.0 && confidence < 1.0 => Ok(confidence),
+        _ => Err(format!("{value} is not a confidence level between 0 and 1")),
+    }
+}
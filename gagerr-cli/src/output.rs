@@ -0,0 +1,159 @@
+use crate::report::{Report, Table};
+use serde_json::{json, Map, Value};
+use std::io::{self, Write};
+
+/// Tables aligned in columns, one block per file, ending with the verdict.
+pub fn text(reports: &[Report], out: &mut impl Write) -> io::Result<()> {
+    for report in reports {
+        writeln!(out, "{}", report.file)?;
+        writeln!(out)?;
+        for table in report.tables.iter() {
+            text_table(table, out)?;
+            writeln!(out)?;
+        }
+        for note in report.notes.iter() {
+            writeln!(out, "{}", note)?;
+        }
+        writeln!(
+            out,
+            "Total Gage R&R is {:.2}% of the study variation ({})",
+            report.verdict.study_var,
+            report.verdict.rating()
+        )?;
+        if report.verdict.accepted() {
+            writeln!(out, "Accepted")?;
+        } else {
+            writeln!(out, "Rejected: {}", report.verdict.failures.join("; "))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn text_table(table: &Table, out: &mut impl Write) -> io::Result<()> {
+    let cells: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            row.values
+                .iter()
+                .zip(table.columns.iter())
+                .map(|(v, c)| {
+                    v.map(|v| format!("{:.*}", c.decimals, v))
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect();
+    let source_width = table
+        .rows
+        .iter()
+        .map(|r| r.source.chars().count())
+        .chain(["Source".len()])
+        .max()
+        .unwrap_or_default();
+    let widths: Vec<usize> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            cells
+                .iter()
+                .map(|row| row[i].len())
+                .chain([c.name.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let line = |source: &str, values: Vec<&str>| {
+        let mut line = format!("{:<source_width$}", source);
+        for (value, width) in values.iter().zip(widths.iter()) {
+            line.push_str(&format!("  {:>width$}", value));
+        }
+        line.trim_end().to_owned()
+    };
+    writeln!(out, "{}", table.title)?;
+    let headers = table.columns.iter().map(|c| c.name.as_str()).collect();
+    writeln!(out, "{}", line("Source", headers))?;
+    for (row, values) in table.rows.iter().zip(cells.iter()) {
+        writeln!(
+            out,
+            "{}",
+            line(row.source, values.iter().map(|v| v.as_str()).collect())
+        )?;
+    }
+    Ok(())
+}
+
+/// An array with an object per file. Rows are keyed by column name, and
+/// gaps in a table are `null`.
+pub fn json(reports: &[Report], out: &mut impl Write) -> io::Result<()> {
+    let reports: Vec<Value> = reports
+        .iter()
+        .map(|report| {
+            let tables: Vec<Value> = report
+                .tables
+                .iter()
+                .map(|table| {
+                    let rows: Vec<Value> = table
+                        .rows
+                        .iter()
+                        .map(|row| {
+                            let mut object = Map::new();
+                            object.insert("Source".to_owned(), json!(row.source));
+                            for (column, value) in table.columns.iter().zip(row.values.iter()) {
+                                // Degrees of freedom are whole numbers
+                                let value = match value {
+                                    Some(v) if column.decimals == 0 => json!(*v as u64),
+                                    _ => json!(value),
+                                };
+                                object.insert(column.name.clone(), value);
+                            }
+                            Value::Object(object)
+                        })
+                        .collect();
+                    json!({ "title": table.title, "rows": rows })
+                })
+                .collect();
+            json!({
+                "file": report.file,
+                "tables": tables,
+                "notes": report.notes,
+                "study_var": report.verdict.study_var,
+                "tolerance": report.verdict.tolerance,
+                "ndc": report.verdict.ndc,
+                "rating": report.verdict.rating(),
+                "accepted": report.verdict.accepted(),
+                "failures": report.verdict.failures,
+            })
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut *out, &reports)?;
+    writeln!(out)
+}
+
+/// One value per line, so that the tables of any number of files fit under
+/// a single header.
+pub fn csv(reports: &[Report], out: &mut impl Write) -> io::Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(["File", "Table", "Source", "Column", "Value"])?;
+    for report in reports {
+        for table in report.tables.iter() {
+            for row in table.rows.iter() {
+                for (column, value) in table.columns.iter().zip(row.values.iter()) {
+                    if let Some(value) = value {
+                        writer.write_record([
+                            report.file.as_str(),
+                            table.title,
+                            row.source,
+                            column.name.as_str(),
+                            value.to_string().as_str(),
+                        ])?;
+                    }
+                }
+            }
+        }
+    }
+    writer.flush()
+}
@@ -0,0 +1,447 @@
+use gagerr_core::confidence_interval::{Interval, VarCompIntervals};
+use gagerr_core::gage_study_data_set::GageStudyDataSet;
+
+/// A column of a results table and the decimals its values are printed with.
+pub struct Column {
+    pub name: String,
+    pub decimals: usize,
+}
+
+impl Column {
+    fn new(name: &str, decimals: usize) -> Self {
+        Self {
+            name: name.to_owned(),
+            decimals,
+        }
+    }
+}
+
+/// A source of variation and its values, `None` where the table has a gap.
+pub struct Row {
+    pub source: &'static str,
+    pub values: Vec<Option<f64>>,
+}
+
+pub struct Table {
+    pub title: &'static str,
+    pub columns: Vec<Column>,
+    pub rows: Vec<Row>,
+}
+
+/// Limits a measurement system has to meet to be accepted. Percentages are
+/// those of the total Gage R&R.
+pub struct Limits {
+    pub max_study_var: f64,
+    pub max_tolerance: Option<f64>,
+    pub min_ndc: Option<u32>,
+}
+
+/// Total Gage R&R against the limits.
+pub struct Verdict {
+    pub study_var: f64,
+    pub tolerance: Option<f64>,
+    pub ndc: u32,
+    /// Limits that were not met, empty if the gage is accepted
+    pub failures: Vec<String>,
+}
+
+impl Verdict {
+    pub fn new(gagerr: &GageStudyDataSet, limits: &Limits) -> Self {
+        let study_var = gagerr.stddev_total_gagerr / gagerr.stddev_total * 100.0;
+        let tolerance = gagerr
+            .tolerance
+            .map(|t| gagerr.stddev_total_gagerr * gagerr.study_variation / t * 100.0);
+        let mut failures = Vec::new();
+        if study_var > limits.max_study_var {
+            failures.push(format!(
+                "% Study Var {:.2}% is above {}%",
+                study_var, limits.max_study_var
+            ));
+        }
+        if let Some(max) = limits.max_tolerance {
+            match tolerance {
+                Some(t) if t > max => {
+                    failures.push(format!("% Tolerance {:.2}% is above {}%", t, max))
+                }
+                Some(_) => (),
+                None => failures.push("% Tolerance needs spec limits or a tolerance".to_owned()),
+            }
+        }
+        if let Some(min) = limits.min_ndc.filter(|min| gagerr.ndc < *min) {
+            failures.push(format!(
+                "Number of distinct categories {} is below {}",
+                gagerr.ndc, min
+            ));
+        }
+        Self {
+            study_var,
+            tolerance,
+            ndc: gagerr.ndc,
+            failures,
+        }
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// AIAG rating of the % Study Var of the total Gage R&R.
+    pub fn rating(&self) -> &'static str {
+        if self.study_var <= 10.0 {
+            "acceptable"
+        } else if self.study_var <= 30.0 {
+            "marginal"
+        } else {
+            "unacceptable"
+        }
+    }
+}
+
+/// Results of the crossed study of one file.
+pub struct Report {
+    pub file: String,
+    pub tables: Vec<Table>,
+    pub notes: Vec<String>,
+    pub verdict: Verdict,
+}
+
+impl Report {
+    pub fn new(file: String, gagerr: &GageStudyDataSet, limits: &Limits) -> Self {
+        let mut tables = vec![anova_with_interaction(gagerr)];
+        tables.extend(anova_without_interaction(gagerr));
+        tables.push(varcomp(gagerr));
+        tables.push(study_var(gagerr));
+
+        let mut notes = Vec::new();
+        if gagerr.pooling_tested() {
+            notes.push(format!(
+                "α to remove interaction term = {}",
+                gagerr.alpha_to_remove
+            ));
+        }
+        notes.push(if !gagerr.balanced {
+            format!(
+                "Estimated by {} because the design is unbalanced.",
+                gagerr.varcomp_method_used
+            )
+        } else if gagerr.varcomp_method_used != gagerr.varcomp_method {
+            format!(
+                "Estimated by {} because {} did not converge.",
+                gagerr.varcomp_method_used, gagerr.varcomp_method
+            )
+        } else {
+            format!("Estimated by {}.", gagerr.varcomp_method_used)
+        });
+        if !gagerr.truncated_varcomps.is_empty() {
+            notes.push(format!(
+                "Negative estimates set to zero: {}",
+                gagerr.truncated_varcomps.join(", ")
+            ));
+        }
+        notes.push(match &gagerr.varcomp_intervals {
+            // The upper limit is unbounded when the gage variance can be zero
+            Some(ci) => format!(
+                "Number of distinct categories = {}, {:.0}% CI ({}, {})",
+                gagerr.ndc,
+                ci.confidence * 100.0,
+                ci.ndc.lower,
+                if ci.ndc.upper.is_finite() {
+                    ci.ndc.upper.to_string()
+                } else {
+                    "∞".to_owned()
+                },
+            ),
+            None => format!("Number of distinct categories = {}", gagerr.ndc),
+        });
+
+        Self {
+            file,
+            tables,
+            notes,
+            verdict: Verdict::new(gagerr, limits),
+        }
+    }
+}
+
+fn anova_columns() -> Vec<Column> {
+    vec![
+        Column::new("DF", 0),
+        Column::new("SS", 6),
+        Column::new("MS", 6),
+        Column::new("F", 6),
+        Column::new("P", 6),
+    ]
+}
+
+fn anova_with_interaction(gagerr: &GageStudyDataSet) -> Table {
+    let mut rows = vec![Row {
+        source: "Parts",
+        values: vec![
+            Some(gagerr.df_part as f64),
+            Some(gagerr.ss_part),
+            Some(gagerr.ms_part),
+            Some(gagerr.f_part),
+            Some(gagerr.p_part),
+        ],
+    }];
+    if gagerr.df_operator > 0 {
+        rows.push(Row {
+            source: "Operator",
+            values: vec![
+                Some(gagerr.df_operator as f64),
+                Some(gagerr.ss_operator),
+                gagerr.ms_operator,
+                gagerr.f_operator,
+                gagerr.p_operator,
+            ],
+        });
+    }
+    if gagerr.df_part_operator > 0 {
+        rows.push(Row {
+            source: "Parts * Operator",
+            values: vec![
+                Some(gagerr.df_part_operator as f64),
+                Some(gagerr.ss_part_operator),
+                gagerr.ms_part_operator,
+                gagerr.f_part_operator,
+                gagerr.p_part_operator,
+            ],
+        });
+    }
+    rows.push(Row {
+        source: "Repeatability",
+        values: vec![
+            Some(gagerr.df_repeatability as f64),
+            Some(gagerr.ss_repeatability),
+            Some(gagerr.ms_repeatability),
+            None,
+            None,
+        ],
+    });
+    rows.push(Row {
+        source: "Total",
+        values: vec![
+            Some(gagerr.df_total as f64),
+            Some(gagerr.ss_total),
+            None,
+            None,
+            None,
+        ],
+    });
+    Table {
+        title: "ANOVA With Interaction",
+        columns: anova_columns(),
+        rows,
+    }
+}
+
+fn anova_without_interaction(gagerr: &GageStudyDataSet) -> Option<Table> {
+    let reduced = gagerr.reduced_anova.as_ref()?;
+    let mut rows = vec![Row {
+        source: "Parts",
+        values: vec![
+            Some(reduced.df_part as f64),
+            Some(reduced.ss_part),
+            Some(reduced.ms_part),
+            Some(reduced.f_part),
+            Some(reduced.p_part),
+        ],
+    }];
+    if reduced.df_operator > 0 {
+        rows.push(Row {
+            source: "Operator",
+            values: vec![
+                Some(reduced.df_operator as f64),
+                Some(reduced.ss_operator),
+                reduced.ms_operator,
+                reduced.f_operator,
+                reduced.p_operator,
+            ],
+        });
+    }
+    rows.push(Row {
+        source: "Repeatability",
+        values: vec![
+            Some(reduced.df_repeatability as f64),
+            Some(reduced.ss_repeatability),
+            Some(reduced.ms_repeatability),
+            None,
+            None,
+        ],
+    });
+    rows.push(Row {
+        source: "Total",
+        values: vec![
+            Some(reduced.df_total as f64),
+            Some(reduced.ss_total),
+            None,
+            None,
+            None,
+        ],
+    });
+    Some(Table {
+        title: "ANOVA Without Interaction",
+        columns: anova_columns(),
+        rows,
+    })
+}
+
+/// Confidence intervals of a source: of its variance component and of its
+/// share of the total variance, which the total itself does not have.
+type SourceIntervals = (Option<Interval>, Option<Interval>);
+
+/// Intervals of every source, in the order of the rows of the variance
+/// component and study variation tables.
+fn source_intervals(ci: &VarCompIntervals) -> [SourceIntervals; 7] {
+    [
+        (Some(ci.total_gagerr), Some(ci.contribution.total_gagerr)),
+        (Some(ci.repeatability), Some(ci.contribution.repeatability)),
+        (ci.reproducibility, ci.contribution.reproducibility),
+        (ci.operator, ci.contribution.operator),
+        (ci.part_operator, ci.contribution.part_operator),
+        (Some(ci.part), Some(ci.contribution.part)),
+        (Some(ci.total), None),
+    ]
+}
+
+/// Lower and upper limit columns of an interval, named after the column
+/// whose values they bracket.
+fn interval_columns(name: &str, confidence: f64, decimals: usize) -> [Column; 2] {
+    let name = format!("{} {:.0}% CI", name, confidence * 100.0);
+    [
+        Column::new(&format!("{} Lower", name), decimals),
+        Column::new(&format!("{} Upper", name), decimals),
+    ]
+}
+
+fn limits(interval: Option<Interval>) -> [Option<f64>; 2] {
+    [interval.map(|i| i.lower), interval.map(|i| i.upper)]
+}
+
+fn varcomp(gagerr: &GageStudyDataSet) -> Table {
+    let ci = gagerr.varcomp_intervals.as_ref();
+    let intervals = ci.map(source_intervals);
+    let row = |source, varcomp: f64, index: usize| {
+        let mut values = vec![Some(varcomp)];
+        if let Some(intervals) = &intervals {
+            values.extend(limits(intervals[index].0));
+        }
+        values.push(Some(varcomp / gagerr.varcomp_total * 100.0));
+        if let Some(intervals) = &intervals {
+            values.extend(limits(intervals[index].1.map(|i| i.scale(100.0))));
+        }
+        Row { source, values }
+    };
+    let mut rows = vec![
+        row("Total Gage R&R", gagerr.varcomp_total_gagerr, 0),
+        row("Repeatability", gagerr.varcomp_repeatability, 1),
+    ];
+    rows.extend(
+        gagerr
+            .varcomp_reproducibility
+            .map(|v| row("Reproducibility", v, 2)),
+    );
+    rows.extend(gagerr.varcomp_operator.map(|v| row("Operator", v, 3)));
+    rows.extend(
+        gagerr
+            .varcomp_part_operator
+            .map(|v| row("Parts * Operator", v, 4)),
+    );
+    rows.push(row("Part-To-Part", gagerr.varcomp_part, 5));
+    rows.push(row("Total Variation", gagerr.varcomp_total, 6));
+
+    let mut columns = vec![Column::new("VarComp", 6)];
+    if let Some(ci) = ci {
+        columns.extend(interval_columns("VarComp", ci.confidence, 6));
+    }
+    columns.push(Column::new("% Contribution", 2));
+    if let Some(ci) = ci {
+        columns.extend(interval_columns("% Contribution", ci.confidence, 2));
+    }
+    Table {
+        title: "Variance Components",
+        columns,
+        rows,
+    }
+}
+
+fn study_var(gagerr: &GageStudyDataSet) -> Table {
+    let ci = gagerr.varcomp_intervals.as_ref();
+    let intervals = ci.map(source_intervals);
+    let mut columns = vec![Column::new("StdDev (SD)", 6)];
+    if let Some(ci) = ci {
+        columns.extend(interval_columns("StdDev", ci.confidence, 6));
+    }
+    columns.push(Column::new(
+        &format!("Study Var ({:.2} x SD)", gagerr.study_variation),
+        6,
+    ));
+    columns.push(Column::new("% Study Var", 2));
+    if let Some(ci) = ci {
+        columns.extend(interval_columns("% Study Var", ci.confidence, 2));
+    }
+    if gagerr.historical_stddev.is_some() {
+        columns.push(Column::new("% Process", 2));
+    }
+    if gagerr.tolerance.is_some() {
+        columns.push(Column::new("% Tolerance", 2));
+    }
+    // `process_sd` replaces `sd` in the % Process column for the sources
+    // that are recomputed from the historical standard deviation. The
+    // intervals of the standard deviations are the square roots of those of
+    // the variances
+    let row = |source, sd: f64, process_sd: Option<f64>, index: usize| {
+        let mut values = vec![Some(sd)];
+        if let Some(intervals) = &intervals {
+            values.extend(limits(intervals[index].0.map(Interval::sqrt)));
+        }
+        values.push(Some(sd * gagerr.study_variation));
+        values.push(Some(sd / gagerr.stddev_total * 100.0));
+        if let Some(intervals) = &intervals {
+            values.extend(limits(intervals[index].1.map(|i| i.sqrt().scale(100.0))));
+        }
+        if let Some(historical) = gagerr.historical_stddev {
+            values.push(Some(process_sd.unwrap_or(sd) / historical * 100.0));
+        }
+        if let Some(tolerance) = gagerr.tolerance {
+            values.push(Some(sd * gagerr.study_variation / tolerance * 100.0));
+        }
+        Row { source, values }
+    };
+    let mut rows = vec![
+        row("Total Gage R&R", gagerr.stddev_total_gagerr, None, 0),
+        row("Repeatability", gagerr.stddev_repeatability, None, 1),
+    ];
+    rows.extend(
+        gagerr
+            .stddev_reproducibility
+            .map(|sd| row("Reproducibility", sd, None, 2)),
+    );
+    rows.extend(
+        gagerr
+            .stddev_operator
+            .map(|sd| row("Operator", sd, None, 3)),
+    );
+    rows.extend(
+        gagerr
+            .stddev_part_operator
+            .map(|sd| row("Parts * Operator", sd, None, 4)),
+    );
+    rows.push(row(
+        "Part-To-Part",
+        gagerr.stddev_part,
+        gagerr.stddev_part_process,
+        5,
+    ));
+    rows.push(row(
+        "Total Variation",
+        gagerr.stddev_total,
+        gagerr.historical_stddev,
+        6,
+    ));
+    Table {
+        title: "Study Variation",
+        columns,
+        rows,
+    }
+}
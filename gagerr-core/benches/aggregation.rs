@@ -0,0 +1,125 @@
+//! Scaling of the crossed and nested calculations with the number of
+//! measurements. Past a fixed cost for the p-values and confidence
+//! intervals, the time grows linearly: throughput should not drop as the
+//! number of measurements goes up. The unbalanced study, with one
+//! measurement missing, is estimated by REML instead of ANOVA. The charts
+//! group covers what the web app recomputes for its charts whenever the
+//! data changes.
+//!
+//! Run with `cargo bench -p gagerr-core --bench aggregation`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+
+use gagerr_core::gage_study_data::GageStudyData;
+use gagerr_core::gage_study_data_set::GageStudyDataSet;
+use gagerr_core::measurement_plots::MeasurementPlotData;
+use gagerr_core::nested_gage_study_data_set::NestedGageStudyDataSet;
+use gagerr_core::xbar_r_chart::XbarRChartData;
+
+const OPERATORS: u32 = 3;
+const TRIALS: u32 = 3;
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Balanced study of about `n` measurements, with part labels unique per
+/// operator when `nested`.
+fn study_data(n: usize, nested: bool) -> Vec<GageStudyData> {
+    let n_part = (n / (OPERATORS * TRIALS) as usize).max(2);
+    // Deterministic pseudo-random noise
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut noise = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    };
+    let mut data = Vec::with_capacity(n_part * (OPERATORS * TRIALS) as usize);
+    for part in 0..n_part {
+        for operator in 1..=OPERATORS {
+            let label = if nested {
+                format!("P{}-{}", operator, part)
+            } else {
+                format!("P{}", part)
+            };
+            for trial in 1..=TRIALS {
+                let value = 10.0 + part as f64 % 7.0 + 0.1 * operator as f64 + noise();
+                data.push(GageStudyData::new(&label, operator, trial, value));
+            }
+        }
+    }
+    data
+}
+
+fn crossed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("crossed");
+    for n in SIZES {
+        let data = study_data(n, false);
+        group.throughput(Throughput::Elements(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &data, |b, data| {
+            b.iter_batched(
+                || GageStudyDataSet::new_with_data(data.clone()),
+                |study| study.calculate(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn unbalanced(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unbalanced");
+    for n in SIZES {
+        let mut data = study_data(n, false);
+        data.pop();
+        group.throughput(Throughput::Elements(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &data, |b, data| {
+            b.iter_batched(
+                || GageStudyDataSet::new_with_data(data.clone()),
+                |study| study.calculate(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn nested(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested");
+    for n in SIZES {
+        let data = study_data(n, true);
+        group.throughput(Throughput::Elements(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &data, |b, data| {
+            b.iter_batched(
+                || NestedGageStudyDataSet::new_with_data(data.clone()),
+                |study| study.calculate(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn charts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("charts");
+    for n in SIZES {
+        let data = study_data(n, false);
+        group.throughput(Throughput::Elements(data.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &data, |b, data| {
+            b.iter_batched(
+                || (data.clone(), data.clone()),
+                |(xbar_r, plots)| {
+                    (
+                        XbarRChartData::new_with_data(xbar_r).calculate(),
+                        MeasurementPlotData::new_with_data(plots).calculate(),
+                    )
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = crossed, unbalanced, nested, charts
+}
+criterion_main!(benches);
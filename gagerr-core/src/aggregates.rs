@@ -0,0 +1,103 @@
+use crate::gage_study_data::GageStudyData;
+use std::collections::HashMap;
+
+/// Sum, count and extremes of a group of measurements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tally {
+    pub sum: f64,
+    pub count: u32,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self {
+            sum: 0.0,
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Tally {
+    pub fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    pub fn range(&self) -> f64 {
+        self.max - self.min
+    }
+}
+
+/// Measurements tallied by part, operator and part/operator cell in a single
+/// pass. Parts, operators and cells are interned in order of appearance, so
+/// `parts[i]` is the label of part `i` and `part[i]` its tally.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Aggregates {
+    pub parts: Vec<String>,
+    pub operators: Vec<u32>,
+    /// `(part, operator)` indices of every cell
+    pub cells: Vec<(usize, usize)>,
+    /// Cell index of every measurement, in the order of the data
+    pub cell_of: Vec<usize>,
+    pub part: Vec<Tally>,
+    pub operator: Vec<Tally>,
+    pub cell: Vec<Tally>,
+    pub total: Tally,
+}
+
+impl Aggregates {
+    pub fn new(data: &[GageStudyData]) -> Self {
+        let mut aggregates = Self::default();
+        let mut part_ids: HashMap<&str, usize> = HashMap::new();
+        let mut operator_ids: HashMap<u32, usize> = HashMap::new();
+        let mut cell_ids: HashMap<(usize, usize), usize> = HashMap::new();
+        aggregates.cell_of.reserve(data.len());
+        for d in data.iter() {
+            let part = *part_ids.entry(d.part.as_str()).or_insert_with(|| {
+                aggregates.parts.push(d.part.clone());
+                aggregates.part.push(Tally::default());
+                aggregates.parts.len() - 1
+            });
+            let operator = *operator_ids.entry(d.operator).or_insert_with(|| {
+                aggregates.operators.push(d.operator);
+                aggregates.operator.push(Tally::default());
+                aggregates.operators.len() - 1
+            });
+            let cell = *cell_ids.entry((part, operator)).or_insert_with(|| {
+                aggregates.cells.push((part, operator));
+                aggregates.cell.push(Tally::default());
+                aggregates.cells.len() - 1
+            });
+            aggregates.part[part].add(d.value);
+            aggregates.operator[operator].add(d.value);
+            aggregates.cell[cell].add(d.value);
+            aggregates.total.add(d.value);
+            aggregates.cell_of.push(cell);
+        }
+        aggregates
+    }
+
+    /// Whether every part was measured by every operator.
+    pub fn is_complete(&self) -> bool {
+        self.cells.len() == self.parts.len() * self.operators.len()
+    }
+
+    /// Sum of squared differences between the group means and the overall
+    /// mean, weighted by the number of measurements in each group.
+    pub fn ss_between(groups: &[Tally], mean: f64) -> f64 {
+        groups
+            .iter()
+            .map(|g| g.count as f64 * (g.mean() - mean).powi(2))
+            .sum()
+    }
+}
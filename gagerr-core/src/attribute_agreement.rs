@@ -0,0 +1,657 @@
+use crate::gage_study_data::GageStudyData;
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Two-sided 95% standard normal quantile.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Number of parts on which the ratings matched, with an exact (Clopper-Pearson)
+/// 95% confidence interval for the percentage.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Agreement {
+    pub inspected: u32,
+    pub matched: u32,
+    pub percent: f64,
+    pub ci_lower: f64,
+    pub ci_upper: f64,
+}
+
+/// Kappa for one category, or over all categories when `category` is `None`.
+/// `se` is the standard error under the hypothesis of chance agreement and is
+/// used for the z test. Kappa and everything derived from it are `None` when
+/// a category was given in every rating or in none, as agreement on it cannot
+/// be told apart from chance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct KappaRow {
+    pub category: Option<String>,
+    pub kappa: Option<f64>,
+    pub se: Option<f64>,
+    pub z: Option<f64>,
+    pub p: Option<f64>,
+    pub ci_lower: Option<f64>,
+    pub ci_upper: Option<f64>,
+}
+
+/// Kendall's coefficient of concordance (chi-square test with `df`) or
+/// correlation coefficient (z test, no `df`).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct KendallRow {
+    pub coefficient: f64,
+    pub statistic: f64,
+    pub df: Option<f64>,
+    pub p: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AppraiserAgreement {
+    pub operator: u32,
+    /// Agreement of the appraiser with themselves across trials
+    pub within: Option<Agreement>,
+    pub within_kappa: Vec<KappaRow>,
+    pub within_kendall: Option<KendallRow>,
+    /// Agreement of every trial of the appraiser with the standard
+    pub vs_standard: Option<Agreement>,
+    pub vs_standard_kappa: Vec<KappaRow>,
+    pub vs_standard_kendall: Option<KendallRow>,
+}
+
+/// Attribute agreement analysis of categorical ratings, e.g. pass/fail or
+/// visual grades, optionally against a known standard per part.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AttributeAgreement {
+    pub data: Vec<GageStudyData>,
+    /// Ratings are ordered grades, so Kendall's coefficients apply
+    pub ordinal: bool,
+    pub categories: Vec<String>,
+    pub n_part: u32,
+    pub appraisers: Vec<AppraiserAgreement>,
+    pub between: Option<Agreement>,
+    pub between_kappa: Vec<KappaRow>,
+    pub between_kendall: Option<KendallRow>,
+    pub all_vs_standard: Option<Agreement>,
+    pub all_vs_standard_kappa: Vec<KappaRow>,
+    pub all_vs_standard_kendall: Option<KendallRow>,
+}
+
+impl Default for AttributeAgreement {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AttributeAgreement {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::<GageStudyData>::new(),
+            ordinal: false,
+            categories: Vec::new(),
+            n_part: 0,
+            appraisers: Vec::new(),
+            between: None,
+            between_kappa: Vec::new(),
+            between_kendall: None,
+            all_vs_standard: None,
+            all_vs_standard_kappa: Vec::new(),
+            all_vs_standard_kendall: None,
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>) -> Self {
+        let mut study = Self::new();
+        study.data = data;
+        study
+    }
+
+    pub fn calculate_mut(&mut self) -> &mut Self {
+        self.categories = self.unique_categories();
+        let ratings = self.ratings();
+        let standards = self.standards();
+        self.n_part = ratings.len() as u32;
+        let operators: BTreeSet<u32> = self
+            .data
+            .iter()
+            .filter(|d| d.rating.is_some())
+            .map(|d| d.operator)
+            .collect();
+
+        self.appraisers = operators
+            .iter()
+            .map(|&operator| {
+                let own: BTreeMap<&String, Vec<(u32, usize)>> = ratings
+                    .iter()
+                    .map(|(part, r)| {
+                        let r = r.iter().filter(|(o, _, _)| *o == operator);
+                        (part, r.map(|(_, t, c)| (*t, *c)).collect())
+                    })
+                    .filter(|(_, r): &(_, Vec<_>)| !r.is_empty())
+                    .collect();
+                let trials: Vec<Vec<usize>> = own
+                    .values()
+                    .map(|r| r.iter().map(|(_, c)| *c).collect())
+                    .collect();
+                let repeated = trials.iter().any(|t| t.len() > 1);
+                let pairs = standard_pairs(&own, &standards);
+                AppraiserAgreement {
+                    operator,
+                    within: repeated.then(|| agreement(trials.iter())),
+                    within_kappa: if repeated {
+                        fleiss_kappa(&trials, &self.categories)
+                    } else {
+                        Vec::new()
+                    },
+                    within_kendall: (repeated && self.ordinal)
+                        .then(|| kendall_w(&own_by_trial(&own)))
+                        .flatten(),
+                    vs_standard: (!pairs.is_empty()).then(|| {
+                        agreement(own.iter().filter_map(|(part, r)| {
+                            let standard = standards.get(*part)?;
+                            Some(
+                                r.iter()
+                                    .map(|(_, c)| *c)
+                                    .chain([*standard])
+                                    .collect::<Vec<_>>(),
+                            )
+                        }))
+                    }),
+                    vs_standard_kappa: cohen_kappa(&pairs, &self.categories),
+                    vs_standard_kendall: self.ordinal.then(|| kendall_tau(&pairs)).flatten(),
+                }
+            })
+            .collect();
+
+        let all: Vec<Vec<usize>> = ratings
+            .values()
+            .map(|r| r.iter().map(|(_, _, c)| *c).collect())
+            .collect();
+        let multiple = operators.len() > 1;
+        self.between = multiple.then(|| agreement(all.iter()));
+        self.between_kappa = if multiple {
+            fleiss_kappa(&all, &self.categories)
+        } else {
+            Vec::new()
+        };
+        self.between_kendall = (multiple && self.ordinal)
+            .then(|| kendall_w(&by_rater(&ratings)))
+            .flatten();
+
+        let pairs: Vec<(usize, usize)> = ratings
+            .iter()
+            .filter_map(|(part, r)| Some((r, *standards.get(part)?)))
+            .flat_map(|(r, s)| r.iter().map(move |(_, _, c)| (*c, s)))
+            .collect();
+        self.all_vs_standard = (multiple && !pairs.is_empty()).then(|| {
+            agreement(ratings.iter().filter_map(|(part, r)| {
+                let standard = standards.get(part)?;
+                Some(
+                    r.iter()
+                        .map(|(_, _, c)| *c)
+                        .chain([*standard])
+                        .collect::<Vec<_>>(),
+                )
+            }))
+        });
+        self.all_vs_standard_kappa = if multiple {
+            cohen_kappa(&pairs, &self.categories)
+        } else {
+            Vec::new()
+        };
+        self.all_vs_standard_kendall = (multiple && self.ordinal)
+            .then(|| kendall_tau(&pairs))
+            .flatten();
+        self
+    }
+
+    pub fn calculate(mut self) -> Self {
+        self.calculate_mut();
+        self
+    }
+
+    /// Every rating and standard that occurs, numerically ordered when they
+    /// are all numbers so that ordinal grades rank correctly.
+    pub fn unique_categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = self
+            .data
+            .iter()
+            .flat_map(|d| d.rating.iter().chain(d.standard.iter()))
+            .cloned()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+        let numbers: Option<Vec<f64>> = categories.iter().map(|c| c.parse().ok()).collect();
+        if let Some(numbers) = numbers {
+            let mut indexed: Vec<(f64, String)> = numbers.into_iter().zip(categories).collect();
+            indexed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            categories = indexed.into_iter().map(|(_, c)| c).collect();
+        }
+        categories
+    }
+
+    fn category(&self, rating: &str) -> usize {
+        self.categories
+            .iter()
+            .position(|c| c == rating)
+            .unwrap_or(0)
+    }
+
+    /// `(operator, trial, category)` of every rating, by part.
+    fn ratings(&self) -> BTreeMap<String, Vec<(u32, u32, usize)>> {
+        let mut ratings: BTreeMap<String, Vec<(u32, u32, usize)>> = BTreeMap::new();
+        for d in self.data.iter() {
+            if let Some(rating) = &d.rating {
+                ratings.entry(d.part.clone()).or_default().push((
+                    d.operator,
+                    d.trial,
+                    self.category(rating),
+                ));
+            }
+        }
+        for r in ratings.values_mut() {
+            r.sort_unstable();
+        }
+        ratings
+    }
+
+    /// Category of the standard of every part that has one.
+    fn standards(&self) -> BTreeMap<String, usize> {
+        let mut standards = BTreeMap::new();
+        for d in self.data.iter() {
+            if let Some(standard) = &d.standard {
+                standards
+                    .entry(d.part.clone())
+                    .or_insert_with(|| self.category(standard));
+            }
+        }
+        standards
+    }
+}
+
+fn all_equal(categories: &[usize]) -> bool {
+    categories.windows(2).all(|w| w[0] == w[1])
+}
+
+/// Agreement over parts, each given as every category it was rated.
+fn agreement<P: AsRef<[usize]>>(parts: impl Iterator<Item = P>) -> Agreement {
+    let (mut inspected, mut matched) = (0, 0);
+    for part in parts {
+        inspected += 1;
+        if all_equal(part.as_ref()) {
+            matched += 1;
+        }
+    }
+    let (x, n) = (matched as f64, inspected as f64);
+    let ci_lower = if matched == 0 {
+        0.0
+    } else {
+        stats::inc_beta_inv(x, n - x + 1.0, 0.025)
+    };
+    let ci_upper = if matched == inspected {
+        1.0
+    } else {
+        stats::inc_beta_inv(x + 1.0, n - x, 0.975)
+    };
+    Agreement {
+        inspected,
+        matched,
+        percent: x / n * 100.0,
+        ci_lower: ci_lower * 100.0,
+        ci_upper: ci_upper * 100.0,
+    }
+}
+
+/// `(rating, standard)` pairs of one appraiser.
+fn standard_pairs(
+    own: &BTreeMap<&String, Vec<(u32, usize)>>,
+    standards: &BTreeMap<String, usize>,
+) -> Vec<(usize, usize)> {
+    own.iter()
+        .filter_map(|(part, r)| Some((r, *standards.get(*part)?)))
+        .flat_map(|(r, s)| r.iter().map(move |(_, c)| (*c, s)))
+        .collect()
+}
+
+/// Ratings of one appraiser as a trial by part matrix, if every trial rated
+/// every part.
+fn own_by_trial(own: &BTreeMap<&String, Vec<(u32, usize)>>) -> Option<Vec<Vec<f64>>> {
+    let trials: BTreeSet<u32> = own.values().flatten().map(|(t, _)| *t).collect();
+    trials
+        .iter()
+        .map(|trial| {
+            own.values()
+                .map(|r| r.iter().find(|(t, _)| t == trial).map(|(_, c)| *c as f64))
+                .collect()
+        })
+        .collect()
+}
+
+/// Ratings as an (operator, trial) by part matrix, if every rater rated every
+/// part.
+fn by_rater(ratings: &BTreeMap<String, Vec<(u32, u32, usize)>>) -> Option<Vec<Vec<f64>>> {
+    let raters: BTreeSet<(u32, u32)> = ratings
+        .values()
+        .flatten()
+        .map(|(o, t, _)| (*o, *t))
+        .collect();
+    raters
+        .iter()
+        .map(|rater| {
+            ratings
+                .values()
+                .map(|r| {
+                    r.iter()
+                        .find(|(o, t, _)| (*o, *t) == *rater)
+                        .map(|(_, _, c)| *c as f64)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn kappa_row(category: Option<String>, kappa: Option<f64>, se: f64, se_ci: f64) -> KappaRow {
+    let z = kappa.map(|k| k / se);
+    KappaRow {
+        category,
+        kappa,
+        se: kappa.map(|_| se),
+        z,
+        p: z.map(stats::norm_sf_two_sided),
+        ci_lower: kappa.map(|k| k - Z_95 * se_ci),
+        ci_upper: kappa.map(|k| k + Z_95 * se_ci),
+    }
+}
+
+/// Fleiss' kappa per category and overall (last row), for parts that all
+/// received the same number of ratings.
+fn fleiss_kappa(parts: &[Vec<usize>], categories: &[String]) -> Vec<KappaRow> {
+    let m = match parts.first() {
+        Some(first) if first.len() > 1 && parts.iter().all(|p| p.len() == first.len()) => {
+            first.len() as f64
+        }
+        _ => return Vec::new(),
+    };
+    let n = parts.len() as f64;
+    let counts: Vec<Vec<f64>> = parts
+        .iter()
+        .map(|p| {
+            (0..categories.len())
+                .map(|j| p.iter().filter(|c| **c == j).count() as f64)
+                .collect()
+        })
+        .collect();
+    let pq: Vec<f64> = (0..categories.len())
+        .map(|j| {
+            let p = counts.iter().map(|c| c[j]).sum::<f64>() / (n * m);
+            p * (1.0 - p)
+        })
+        .collect();
+    let se_category = (2.0 / (n * m * (m - 1.0))).sqrt();
+    let mut rows: Vec<KappaRow> = categories
+        .iter()
+        .enumerate()
+        .map(|(j, category)| {
+            let disagreement: f64 = counts.iter().map(|c| c[j] * (m - c[j])).sum();
+            let kappa = (pq[j] > 0.0).then(|| 1.0 - disagreement / (n * m * (m - 1.0) * pq[j]));
+            kappa_row(Some(category.clone()), kappa, se_category, se_category)
+        })
+        .collect();
+    let sum_pq: f64 = pq.iter().sum();
+    let sum_sq: f64 = counts.iter().flatten().map(|x| x * x).sum();
+    let kappa = (sum_pq > 0.0).then(|| 1.0 - (n * m * m - sum_sq) / (n * m * (m - 1.0) * sum_pq));
+    // q_j - p_j = 1 - 2 p_j
+    let skew: f64 = counts
+        .iter()
+        .fold(vec![0.0; categories.len()], |mut acc, c| {
+            acc.iter_mut().zip(c).for_each(|(a, x)| *a += x);
+            acc
+        })
+        .iter()
+        .zip(pq.iter())
+        .map(|(total, pq)| pq * (1.0 - 2.0 * total / (n * m)))
+        .sum();
+    let se =
+        2.0_f64.sqrt() / (sum_pq * (n * m * (m - 1.0)).sqrt()) * (sum_pq.powi(2) - skew).sqrt();
+    rows.push(kappa_row(None, kappa, se, se));
+    rows
+}
+
+/// Cohen's kappa of `(rating, standard)` pairs per category and overall (last
+/// row). The confidence interval uses the large-sample standard error.
+fn cohen_kappa(pairs: &[(usize, usize)], categories: &[String]) -> Vec<KappaRow> {
+    if pairs.is_empty() {
+        return Vec::new();
+    }
+    let cohen = |pairs: &[(usize, usize)], k: usize| {
+        let n = pairs.len() as f64;
+        let mut rows = vec![0.0; k];
+        let mut cols = vec![0.0; k];
+        let mut po = 0.0;
+        for (r, s) in pairs {
+            rows[*r] += 1.0 / n;
+            cols[*s] += 1.0 / n;
+            if r == s {
+                po += 1.0 / n;
+            }
+        }
+        let pe: f64 = rows.iter().zip(cols.iter()).map(|(r, c)| r * c).sum();
+        let spread: f64 = rows
+            .iter()
+            .zip(cols.iter())
+            .map(|(r, c)| r * c * (r + c))
+            .sum();
+        let kappa = (pe < 1.0).then(|| (po - pe) / (1.0 - pe));
+        let se = (pe + pe * pe - spread).sqrt() / ((1.0 - pe) * n.sqrt());
+        let se_ci = (po * (1.0 - po) / n).sqrt() / (1.0 - pe);
+        (kappa, se, se_ci)
+    };
+    let mut rows: Vec<KappaRow> = categories
+        .iter()
+        .enumerate()
+        .map(|(j, category)| {
+            let binary: Vec<(usize, usize)> = pairs
+                .iter()
+                .map(|(r, s)| ((*r == j) as usize, (*s == j) as usize))
+                .collect();
+            let (kappa, se, se_ci) = cohen(&binary, 2);
+            kappa_row(Some(category.clone()), kappa, se, se_ci)
+        })
+        .collect();
+    let (kappa, se, se_ci) = cohen(pairs, categories.len());
+    rows.push(kappa_row(None, kappa, se, se_ci));
+    rows
+}
+
+/// Ranks of `values`, averaged over ties, and the tie correction
+/// sum(t^3 - t) over groups of tied values.
+fn ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for i in &order[start..end] {
+            ranks[*i] = rank;
+        }
+        let t = (end - start) as f64;
+        ties += t.powi(3) - t;
+        start = end;
+    }
+    (ranks, ties)
+}
+
+/// Kendall's coefficient of concordance W of a rater by part matrix.
+fn kendall_w(raters: &Option<Vec<Vec<f64>>>) -> Option<KendallRow> {
+    let raters = raters.as_ref()?;
+    let m = raters.len() as f64;
+    let n = raters.first()?.len() as f64;
+    if m < 2.0 || n < 2.0 {
+        return None;
+    }
+    let mut sums = vec![0.0; n as usize];
+    let mut ties = 0.0;
+    for rater in raters {
+        let (r, t) = ranks(rater);
+        sums.iter_mut().zip(r).for_each(|(s, r)| *s += r);
+        ties += t;
+    }
+    let mean = m * (n + 1.0) / 2.0;
+    let s: f64 = sums.iter().map(|r| (r - mean).powi(2)).sum();
+    let w = 12.0 * s / (m * m * (n.powi(3) - n) - m * ties);
+    let chi_square = m * (n - 1.0) * w;
+    Some(KendallRow {
+        coefficient: w,
+        statistic: chi_square,
+        df: Some(n - 1.0),
+        p: stats::chi2_sf(chi_square, n - 1.0),
+    })
+}
+
+/// Kendall's tau-b between ratings and standards, with a tie corrected z test.
+fn kendall_tau(pairs: &[(usize, usize)]) -> Option<KendallRow> {
+    if pairs.len() < 3 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let mut s = 0.0;
+    for (i, a) in pairs.iter().enumerate() {
+        for b in &pairs[i + 1..] {
+            s += (a.0.cmp(&b.0) as i32 * a.1.cmp(&b.1) as i32) as f64;
+        }
+    }
+    let tie_groups = |values: Vec<usize>| {
+        let mut counts = BTreeMap::new();
+        for v in values {
+            *counts.entry(v).or_insert(0.0) += 1.0;
+        }
+        counts.into_values().collect::<Vec<f64>>()
+    };
+    let x = tie_groups(pairs.iter().map(|p| p.0).collect());
+    let y = tie_groups(pairs.iter().map(|p| p.1).collect());
+    let pairs_total = n * (n - 1.0) / 2.0;
+    let tied = |t: &[f64]| t.iter().map(|t| t * (t - 1.0) / 2.0).sum::<f64>();
+    let tau = s / ((pairs_total - tied(&x)) * (pairs_total - tied(&y))).sqrt();
+    let sum = |t: &[f64], f: fn(f64) -> f64| t.iter().map(|t| f(*t)).sum::<f64>();
+    let v0 = n * (n - 1.0) * (2.0 * n + 5.0);
+    let v5 = |t: f64| t * (t - 1.0) * (2.0 * t + 5.0);
+    let v1 = |t: f64| t * (t - 1.0);
+    let v2 = |t: f64| t * (t - 1.0) * (t - 2.0);
+    let var = (v0 - sum(&x, v5) - sum(&y, v5)) / 18.0
+        + sum(&x, v1) * sum(&y, v1) / (2.0 * n * (n - 1.0))
+        + sum(&x, v2) * sum(&y, v2) / (9.0 * n * (n - 1.0) * (n - 2.0));
+    let z = s / var.sqrt();
+    Some(KendallRow {
+        coefficient: tau,
+        statistic: z,
+        df: None,
+        p: stats::norm_sf_two_sided(z),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    fn categories(k: usize) -> Vec<String> {
+        (1..=k).map(|c| c.to_string()).collect()
+    }
+
+    /// Fleiss (1971): ten subjects, each classified by fourteen raters into
+    /// one of five categories. The counts per category are expanded into one
+    /// rating per rater.
+    fn fleiss_example() -> Vec<Vec<usize>> {
+        let counts = [
+            [0, 0, 0, 0, 14],
+            [0, 2, 6, 4, 2],
+            [0, 0, 3, 5, 6],
+            [0, 3, 9, 2, 0],
+            [2, 2, 8, 1, 1],
+            [7, 7, 0, 0, 0],
+            [3, 2, 6, 3, 0],
+            [2, 5, 3, 2, 2],
+            [6, 5, 2, 1, 0],
+            [0, 2, 2, 3, 7],
+        ];
+        counts
+            .iter()
+            .map(|c| {
+                c.iter()
+                    .enumerate()
+                    .flat_map(|(j, count)| std::iter::repeat_n(j, *count))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fleiss_kappa_matches_reference_example() {
+        let rows = fleiss_kappa(&fleiss_example(), &categories(5));
+        assert_eq!(rows.len(), 6);
+        let expected = [0.201282, 0.079670, 0.171598, 0.030381, 0.507657];
+        for (row, expected) in rows.iter().zip(expected) {
+            assert_close(row.kappa.unwrap(), expected, 1e-6);
+            assert_close(row.se.unwrap(), 0.033150, 1e-6);
+        }
+        let overall = rows.last().unwrap();
+        assert_eq!(overall.category, None);
+        assert_close(overall.kappa.unwrap(), 0.209931, 1e-6);
+        assert_close(overall.se.unwrap(), 0.016965, 1e-6);
+    }
+
+    #[test]
+    fn fleiss_kappa_is_undefined_for_unused_category() {
+        let parts = vec![vec![0, 0, 1], vec![1, 1, 1], vec![0, 0, 0]];
+        let rows = fleiss_kappa(&parts, &categories(3));
+        assert!(rows[0].kappa.is_some());
+        assert_eq!(rows[2].kappa, None);
+        assert_eq!(rows[2].p, None);
+        assert!(rows[3].kappa.is_some());
+    }
+
+    /// Two raters of fifty items: 20 both yes, 15 both no, 5 yes/no and 10
+    /// no/yes, so po = 0.7, pe = 0.5 and kappa = 0.4.
+    fn cohen_example() -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (pair, count) in [((0, 0), 20), ((0, 1), 5), ((1, 0), 10), ((1, 1), 15)] {
+            pairs.extend(std::iter::repeat_n(pair, count));
+        }
+        pairs
+    }
+
+    #[test]
+    fn cohen_kappa_matches_reference_example() {
+        let rows = cohen_kappa(&cohen_example(), &categories(2));
+        assert_eq!(rows.len(), 3);
+        for row in rows.iter() {
+            assert_close(row.kappa.unwrap(), 0.4, 1e-12);
+            assert_close(row.se.unwrap(), 0.138564, 1e-6);
+            assert_close(row.z.unwrap(), 2.886751, 1e-6);
+            assert_close(row.ci_lower.unwrap(), 0.145958, 1e-5);
+            assert_close(row.ci_upper.unwrap(), 0.654042, 1e-5);
+        }
+    }
+
+    #[test]
+    fn cohen_kappa_is_undefined_when_always_rated_the_same() {
+        let rows = cohen_kappa(&[(0, 0), (0, 0), (0, 0)], &categories(2));
+        assert!(rows
+            .iter()
+            .all(|r| r.kappa.is_none() && r.ci_lower.is_none()));
+    }
+}
@@ -0,0 +1,418 @@
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::cell::RefCell;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Interval {
+    pub fn sqrt(self) -> Self {
+        Self {
+            lower: self.lower.sqrt(),
+            upper: self.upper.sqrt(),
+        }
+    }
+
+    pub fn scale(self, factor: f64) -> Self {
+        Self {
+            lower: self.lower * factor,
+            upper: self.upper * factor,
+        }
+    }
+}
+
+/// One mean square of an ANOVA, weighted by its coefficient in a linear
+/// combination that estimates a variance component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanSquareTerm<'a> {
+    /// Identifies the mean square, so that combinations can be added
+    pub source: &'a str,
+    pub coef: f64,
+    pub ms: f64,
+    pub df: f64,
+}
+
+impl<'a> MeanSquareTerm<'a> {
+    pub fn new(source: &'a str, coef: f64, ms: f64, df: u32) -> Self {
+        Self {
+            source,
+            coef,
+            ms,
+            df: df as f64,
+        }
+    }
+}
+
+/// Sum of two linear combinations of mean squares.
+pub fn combine<'a>(a: &[MeanSquareTerm<'a>], b: &[MeanSquareTerm<'a>]) -> Vec<MeanSquareTerm<'a>> {
+    let mut terms = a.to_vec();
+    for term in b {
+        match terms.iter_mut().find(|t| t.source == term.source) {
+            Some(t) => t.coef += term.coef,
+            None => terms.push(*term),
+        }
+    }
+    terms.retain(|t| t.coef != 0.0);
+    terms
+}
+
+/// Chi-square interval for the variance a single mean square estimates.
+pub fn chi_square_interval(ms: f64, df: u32, confidence: f64) -> Interval {
+    let alpha = 1.0 - confidence;
+    let df = df as f64;
+    Interval {
+        lower: df * ms / stats::chi2_quantile(1.0 - alpha / 2.0, df),
+        upper: df * ms / stats::chi2_quantile(alpha / 2.0, df),
+    }
+}
+
+/// Modified Large Sample interval (Ting, Burdick, Graybill, Jeyaratnam and
+/// Lu 1990) for a linear combination of expected mean squares with positive
+/// and negative coefficients. The lower limit is truncated at zero.
+pub fn mls_interval(terms: &[MeanSquareTerm], confidence: f64) -> Interval {
+    let interval = mls_limits(terms, &Quantiles::new(confidence));
+    Interval {
+        lower: interval.lower.max(0.0),
+        ..interval
+    }
+}
+
+/// MLS interval for the share `numerator / denominator` of a variance in a
+/// larger one, such as a component in the total variance. The limits are
+/// the shares θ at which the MLS interval for `numerator − θ denominator`
+/// stops covering zero (Burdick, Borror and Montgomery 2005), found by
+/// bisection between 0 and 1.
+pub fn mls_ratio_interval<'a>(
+    numerator: &[MeanSquareTerm<'a>],
+    denominator: &[MeanSquareTerm<'a>],
+    confidence: f64,
+) -> Interval {
+    ratio_limits(numerator, denominator, &Quantiles::new(confidence))
+}
+
+fn ratio_limits<'a>(
+    numerator: &[MeanSquareTerm<'a>],
+    denominator: &[MeanSquareTerm<'a>],
+    quantiles: &Quantiles,
+) -> Interval {
+    let difference = |theta: f64| {
+        let scaled: Vec<MeanSquareTerm> = denominator
+            .iter()
+            .map(|t| MeanSquareTerm {
+                coef: -theta * t.coef,
+                ..*t
+            })
+            .collect();
+        mls_limits(&combine(numerator, &scaled), quantiles)
+    };
+    // Both limits of the difference decrease as θ grows
+    let root = |limit: &dyn Fn(f64) -> f64| {
+        if limit(0.0) <= 0.0 {
+            return 0.0;
+        }
+        if limit(1.0) >= 0.0 {
+            return 1.0;
+        }
+        let (mut low, mut high) = (0.0, 1.0);
+        while high - low > 1.0e-10 {
+            let mid = 0.5 * (low + high);
+            if limit(mid) > 0.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        0.5 * (low + high)
+    };
+    Interval {
+        lower: root(&|theta| difference(theta).lower),
+        upper: root(&|theta| difference(theta).upper),
+    }
+}
+
+/// Lower and upper tail quantiles of a confidence level, kept once computed
+/// since the bisection of `mls_ratio_interval` needs the same few over and
+/// over.
+struct Quantiles {
+    tails: (f64, f64),
+    chi2: RefCell<Vec<(f64, Tails)>>,
+    f: RefCell<Vec<((f64, f64), Tails)>>,
+}
+
+/// Lower and upper tail quantiles of a distribution
+type Tails = (f64, f64);
+
+impl Quantiles {
+    fn new(confidence: f64) -> Self {
+        let alpha = 1.0 - confidence;
+        Self {
+            tails: (alpha / 2.0, 1.0 - alpha / 2.0),
+            chi2: RefCell::new(Vec::new()),
+            f: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn chi2(&self, df: f64) -> Tails {
+        let (lo, hi) = self.tails;
+        let mut known = self.chi2.borrow_mut();
+        match known.iter().find(|(d, _)| *d == df) {
+            Some((_, q)) => *q,
+            None => {
+                let q = (stats::chi2_quantile(lo, df), stats::chi2_quantile(hi, df));
+                known.push((df, q));
+                q
+            }
+        }
+    }
+
+    fn f(&self, df1: f64, df2: f64) -> Tails {
+        let (lo, hi) = self.tails;
+        let mut known = self.f.borrow_mut();
+        match known.iter().find(|(d, _)| *d == (df1, df2)) {
+            Some((_, q)) => *q,
+            None => {
+                let q = (
+                    stats::f_quantile(lo, df1, df2),
+                    stats::f_quantile(hi, df1, df2),
+                );
+                known.push(((df1, df2), q));
+                q
+            }
+        }
+    }
+}
+
+/// MLS limits without truncation, so that they can change sign.
+fn mls_limits(terms: &[MeanSquareTerm], quantiles: &Quantiles) -> Interval {
+    let g = |df: f64| 1.0 - df / quantiles.chi2(df).1;
+    let h = |df: f64| df / quantiles.chi2(df).0 - 1.0;
+    let estimate: f64 = terms.iter().map(|t| t.coef * t.ms).sum();
+    let positive: Vec<&MeanSquareTerm> = terms.iter().filter(|t| t.coef > 0.0).collect();
+    let negative: Vec<&MeanSquareTerm> = terms.iter().filter(|t| t.coef < 0.0).collect();
+
+    let weight = |t: &MeanSquareTerm| (t.coef * t.ms).abs();
+    let mut v_lower = 0.0;
+    let mut v_upper = 0.0;
+    for p in positive.iter() {
+        v_lower += (g(p.df) * weight(p)).powi(2);
+        v_upper += (h(p.df) * weight(p)).powi(2);
+    }
+    for n in negative.iter() {
+        v_lower += (h(n.df) * weight(n)).powi(2);
+        v_upper += (g(n.df) * weight(n)).powi(2);
+    }
+    for p in positive.iter() {
+        for n in negative.iter() {
+            let (f_lo, f_hi) = quantiles.f(p.df, n.df);
+            let g_pn = ((f_hi - 1.0).powi(2) - (g(p.df) * f_hi).powi(2) - h(n.df).powi(2)) / f_hi;
+            let h_pn = ((1.0 - f_lo).powi(2) - (h(p.df) * f_lo).powi(2) - g(n.df).powi(2)) / f_lo;
+            v_lower += g_pn * weight(p) * weight(n);
+            v_upper += h_pn * weight(p) * weight(n);
+        }
+    }
+    if !negative.is_empty() && positive.len() > 1 {
+        for (i, p) in positive.iter().enumerate() {
+            for t in positive.iter().skip(i + 1) {
+                let df = p.df + t.df;
+                let g_star = ((1.0 - df / quantiles.chi2(df).1).powi(2) * df.powi(2)
+                    / (p.df * t.df)
+                    - g(p.df).powi(2) * p.df / t.df
+                    - g(t.df).powi(2) * t.df / p.df)
+                    / (positive.len() - 1) as f64;
+                v_lower += g_star * weight(p) * weight(t);
+            }
+        }
+    }
+    Interval {
+        lower: estimate - v_lower.max(0.0).sqrt(),
+        upper: estimate + v_upper.max(0.0).sqrt(),
+    }
+}
+
+/// Intervals for the share of the total variance of every component, from 0
+/// to 1, i.e. the % Contribution over 100. Their square roots are the
+/// intervals for the share of the total standard deviation, % Study Var.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ContributionIntervals {
+    pub part: Interval,
+    pub operator: Option<Interval>,
+    pub part_operator: Option<Interval>,
+    pub repeatability: Interval,
+    pub reproducibility: Option<Interval>,
+    pub total_gagerr: Interval,
+}
+
+/// Intervals for the variance components of a gage study, and the ndc range
+/// they imply.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VarCompIntervals {
+    pub confidence: f64,
+    pub part: Interval,
+    pub operator: Option<Interval>,
+    pub part_operator: Option<Interval>,
+    pub repeatability: Interval,
+    pub reproducibility: Option<Interval>,
+    pub total_gagerr: Interval,
+    pub total: Interval,
+    pub ndc: Interval,
+    pub contribution: ContributionIntervals,
+}
+
+impl VarCompIntervals {
+    /// Builds the intervals from the mean square combinations of every
+    /// component. Repeatability is a single mean square and gets the exact
+    /// chi-square interval.
+    pub fn new<'a>(
+        confidence: f64,
+        part: &[MeanSquareTerm<'a>],
+        operator: Option<&[MeanSquareTerm<'a>]>,
+        part_operator: Option<&[MeanSquareTerm<'a>]>,
+        repeatability: MeanSquareTerm<'a>,
+    ) -> Self {
+        let interval = |terms: &[MeanSquareTerm]| mls_interval(terms, confidence);
+        let reproducibility = match (operator, part_operator) {
+            (Some(o), Some(po)) => Some(combine(o, po)),
+            (Some(o), None) => Some(o.to_vec()),
+            _ => None,
+        };
+        let total_gagerr = combine(&[repeatability], reproducibility.as_deref().unwrap_or(&[]));
+        let total = combine(&total_gagerr, part);
+        let quantiles = Quantiles::new(confidence);
+        let share = |terms: &[MeanSquareTerm]| ratio_limits(terms, &total, &quantiles);
+        let contribution = ContributionIntervals {
+            part: share(part),
+            operator: operator.map(share),
+            part_operator: part_operator.map(share),
+            repeatability: share(&[repeatability]),
+            reproducibility: reproducibility.as_deref().map(share),
+            total_gagerr: share(&total_gagerr),
+        };
+        let part = interval(part);
+        let total_gagerr = interval(&total_gagerr);
+        // ndc is a ratio, so pair the opposite limits of part and gage
+        let ndc = Interval {
+            lower: (1.41 * (part.lower / total_gagerr.upper).sqrt()).floor(),
+            upper: (1.41 * (part.upper / total_gagerr.lower).sqrt()).floor(),
+        };
+        Self {
+            confidence,
+            part,
+            operator: operator.map(interval),
+            part_operator: part_operator.map(interval),
+            repeatability: chi_square_interval(
+                repeatability.ms,
+                repeatability.df as u32,
+                confidence,
+            ),
+            reproducibility: reproducibility.as_deref().map(interval),
+            total_gagerr,
+            total: interval(&total),
+            ndc,
+            contribution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    #[test]
+    fn chi_square_interval_matches_table() {
+        // χ²(0.025, 10) = 3.2470, χ²(0.975, 10) = 20.4832
+        let interval = chi_square_interval(2.0, 10, 0.95);
+        assert_close(interval.lower, 0.976411, 1e-5);
+        assert_close(interval.upper, 6.159584, 1e-5);
+    }
+
+    #[test]
+    fn mls_interval_of_one_mean_square_is_chi_square_interval() {
+        let interval = mls_interval(&[MeanSquareTerm::new("Repeatability", 1.0, 0.04, 78)], 0.95);
+        let expected = chi_square_interval(0.04, 78, 0.95);
+        assert_close(interval.lower, expected.lower, 1e-9);
+        assert_close(interval.upper, expected.upper, 1e-9);
+    }
+
+    // Reference limits from the MLS formulas of Ting et al. (1990) with
+    // quantiles computed independently to 30 digits.
+    #[test]
+    fn mls_interval_matches_reference_values() {
+        let operator = mls_interval(
+            &[
+                MeanSquareTerm::new("Operator", 1.0 / 30.0, 1.6, 2),
+                MeanSquareTerm::new("Repeatability", -1.0 / 30.0, 0.04, 78),
+            ],
+            0.95,
+        );
+        assert_close(operator.lower, 0.0130941, 1e-6);
+        assert_close(operator.upper, 2.1051769, 1e-5);
+
+        let part = mls_interval(
+            &[
+                MeanSquareTerm::new("Part", 1.0 / 9.0, 9.8, 9),
+                MeanSquareTerm::new("Repeatability", -1.0 / 9.0, 0.04, 78),
+            ],
+            0.95,
+        );
+        assert_close(part.lower, 0.5106646, 1e-5);
+        assert_close(part.upper, 3.6245736, 1e-4);
+    }
+
+    #[test]
+    fn mls_interval_truncates_lower_limit_at_zero() {
+        let interval = mls_interval(
+            &[
+                MeanSquareTerm::new("Part * Operator", 1.0 / 3.0, 0.02, 18),
+                MeanSquareTerm::new("Repeatability", -1.0 / 3.0, 0.046, 60),
+            ],
+            0.95,
+        );
+        assert_eq!(interval.lower, 0.0);
+        assert!(interval.upper > 0.0);
+    }
+
+    #[test]
+    fn mls_ratio_interval_brackets_share() {
+        let part = [
+            MeanSquareTerm::new("Part", 1.0 / 9.0, 9.8, 9),
+            MeanSquareTerm::new("Repeatability", -1.0 / 9.0, 0.04, 78),
+        ];
+        let total = combine(
+            &part,
+            &[MeanSquareTerm::new("Repeatability", 1.0, 0.04, 78)],
+        );
+        let share = (9.8 - 0.04) / 9.0 / ((9.8 - 0.04) / 9.0 + 0.04);
+        let interval = mls_ratio_interval(&part, &total, 0.95);
+        assert!(0.0 < interval.lower && interval.lower < share);
+        assert!(share < interval.upper && interval.upper < 1.0);
+
+        // At the limits, the MLS interval of part − θ total just reaches zero
+        let quantiles = Quantiles::new(0.95);
+        let difference = |theta: f64| {
+            let scaled: Vec<MeanSquareTerm> = total
+                .iter()
+                .map(|t| MeanSquareTerm {
+                    coef: -theta * t.coef,
+                    ..*t
+                })
+                .collect();
+            mls_limits(&combine(&part, &scaled), &quantiles)
+        };
+        assert_close(difference(interval.lower).lower, 0.0, 1e-8);
+        assert_close(difference(interval.upper).upper, 0.0, 1e-8);
+    }
+}
@@ -0,0 +1,90 @@
+// Control chart constant d2 for subgroup sizes 2 through 15, i.e. the
+// expected range of a normal sample in units of sigma.
+const D2: [f64; 14] = [
+    std::f64::consts::FRAC_2_SQRT_PI,
+    1.69257,
+    2.05875,
+    2.32593,
+    2.53441,
+    2.70436,
+    2.84720,
+    2.97003,
+    3.07751,
+    3.17287,
+    3.25846,
+    3.33598,
+    3.40676,
+    3.47193,
+];
+
+// d2* for a single subgroup (g = 1) of sizes 2 through 15, from the AIAG MSA
+// reference manual.
+const D2_STAR_SINGLE: [f64; 14] = [
+    std::f64::consts::SQRT_2,
+    1.91155,
+    2.23887,
+    2.48124,
+    2.67253,
+    2.82981,
+    2.96288,
+    3.07794,
+    3.17905,
+    3.26909,
+    3.35016,
+    3.42378,
+    3.49116,
+    3.55333,
+];
+
+// Control chart constant d3 for subgroup sizes 2 through 15, the standard
+// deviation of the range of a normal sample in units of sigma.
+const D3: [f64; 14] = [
+    0.8525, 0.8884, 0.8798, 0.8641, 0.8480, 0.8332, 0.8198, 0.8078, 0.7971, 0.7873, 0.7785, 0.7704,
+    0.7630, 0.7562,
+];
+
+fn lookup(table: &[f64], m: u32) -> Option<f64> {
+    if m < 2 {
+        return None;
+    }
+    table.get(m as usize - 2).copied()
+}
+
+/// d2 for a subgroup of size `m`.
+pub fn d2(m: u32) -> Option<f64> {
+    lookup(&D2, m)
+}
+
+/// d2* for one subgroup of size `m`.
+pub fn d2_star_single(m: u32) -> Option<f64> {
+    lookup(&D2_STAR_SINGLE, m)
+}
+
+/// AIAG K1 constant (1 / d2) for the number of trials.
+pub fn k1(n_trial: u32) -> Option<f64> {
+    d2(n_trial).map(|d| 1.0 / d)
+}
+
+/// AIAG K2 constant (1 / d2*) for the number of operators.
+pub fn k2(n_operator: u32) -> Option<f64> {
+    d2_star_single(n_operator).map(|d| 1.0 / d)
+}
+
+/// AIAG K3 constant (1 / d2*) for the number of parts.
+pub fn k3(n_part: u32) -> Option<f64> {
+    d2_star_single(n_part).map(|d| 1.0 / d)
+}
+
+/// Control chart constant A2 for subgroups of size `m`: the Xbar chart
+/// limits are the grand mean ± A2 times the average range.
+pub fn a2(m: u32) -> Option<f64> {
+    d2(m).map(|d| 3.0 / (d * (m as f64).sqrt()))
+}
+
+/// Control chart constants D3 and D4 for subgroups of size `m`: the R chart
+/// limits are D3 and D4 times the average range.
+pub fn d3_d4(m: u32) -> Option<(f64, f64)> {
+    let d2 = d2(m)?;
+    let d3 = lookup(&D3, m)?;
+    Some(((1.0 - 3.0 * d3 / d2).max(0.0), 1.0 + 3.0 * d3 / d2))
+}
@@ -0,0 +1,893 @@
+use crate::confidence_interval::{
+    chi_square_interval, combine, mls_interval, mls_ratio_interval, Interval, MeanSquareTerm,
+};
+use crate::gage_error::{check_levels, check_measurements, check_repeats_vary, GageError};
+use crate::gage_study_data::GageStudyData;
+use crate::gage_study_data_set::{ndc, process_stddev_part, truncate_varcomp};
+use crate::spec_limits::SpecLimits;
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// How an additional factor relates to the others in the study.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum FactorRole {
+    /// Every level is combined with every level of the other factors.
+    Crossed,
+    /// Levels only exist within one level of the named factor.
+    NestedIn(String),
+}
+
+/// An additional random factor, read from the CSV column of the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExtraFactor {
+    pub name: String,
+    pub role: FactorRole,
+}
+
+impl ExtraFactor {
+    pub fn new(name: &str, role: FactorRole) -> Self {
+        Self {
+            name: name.to_owned(),
+            role,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AnovaRow {
+    pub source: String,
+    pub df: u32,
+    pub ss: f64,
+    pub ms: Option<f64>,
+    pub f: Option<f64>,
+    /// Degrees of freedom of the F test denominator. Fractional when the
+    /// denominator had to be synthesized from several mean squares.
+    pub df_error: Option<f64>,
+    pub p: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VarCompRow {
+    pub source: String,
+    pub varcomp: f64,
+    pub stddev: f64,
+    /// Whether the row is a component of reproducibility rather than a total.
+    pub indent: bool,
+    pub intervals: Option<VarCompRowIntervals>,
+}
+
+/// Confidence intervals of a variance component and of its share of the
+/// total variance, i.e. the % Contribution over 100.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VarCompRowIntervals {
+    pub varcomp: Interval,
+    /// None for the total variation, whose share is always 1
+    pub contribution: Option<Interval>,
+}
+
+// A term of the model, stored as a bit mask over the factor list. Nested
+// factors always carry the factors they are nested in.
+#[derive(Debug, Clone)]
+struct Term {
+    mask: u32,
+    source: String,
+    df: u32,
+    ss: f64,
+    ms: f64,
+    // Observations per level combination of the term
+    per_cell: f64,
+}
+
+/// Expanded Gage R&R: the crossed part/operator study plus any number of
+/// additional random factors, each crossed or nested, with all two-way
+/// interactions. Higher order interactions are pooled into repeatability.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExpandedGageStudyDataSet {
+    pub data: Vec<GageStudyData>,
+    pub extra_factors: Vec<ExtraFactor>,
+    pub study_variation: f64,
+    pub spec_limits: SpecLimits,
+    /// Tolerance from the spec limits, if any were given
+    pub tolerance: Option<f64>,
+    pub historical_stddev: Option<f64>,
+    pub confidence: f64,
+    pub mean: f64,
+    pub n_total: u32,
+    pub anova: Vec<AnovaRow>,
+    pub varcomp_part: f64,
+    pub varcomp_repeatability: f64,
+    pub varcomp_reproducibility: f64,
+    pub varcomp_total_gagerr: f64,
+    pub varcomp_total: f64,
+    pub varcomp_terms: Vec<(String, f64)>,
+    /// Sources whose negative estimate was set to zero
+    pub truncated_varcomps: Vec<String>,
+    /// Terms left out of the model because they have no degrees of freedom,
+    /// which happens when a nested factor has a single level within every
+    /// level of its parent. Their variation is part of the parent's.
+    pub confounded_terms: Vec<String>,
+    pub ndc: u32,
+    pub stddev_part_process: Option<f64>,
+    /// Intervals of the variance components, in the order of
+    /// `varcomp_rows`. MLS intervals for the sums of mean squares and a
+    /// chi-square interval for repeatability.
+    pub varcomp_intervals: Vec<VarCompRowIntervals>,
+    pub ndc_interval: Option<Interval>,
+}
+
+impl Default for ExpandedGageStudyDataSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExpandedGageStudyDataSet {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::<GageStudyData>::new(),
+            extra_factors: Vec::new(),
+            study_variation: 5.15,
+            spec_limits: SpecLimits::default(),
+            tolerance: None,
+            historical_stddev: None,
+            confidence: 0.95,
+            mean: 0.0,
+            n_total: 0,
+            anova: Vec::new(),
+            varcomp_part: 0.0,
+            varcomp_repeatability: 0.0,
+            varcomp_reproducibility: 0.0,
+            varcomp_total_gagerr: 0.0,
+            varcomp_total: 0.0,
+            varcomp_terms: Vec::new(),
+            truncated_varcomps: Vec::new(),
+            confounded_terms: Vec::new(),
+            ndc: 0,
+            stddev_part_process: None,
+            varcomp_intervals: Vec::new(),
+            ndc_interval: None,
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>, extra_factors: Vec<ExtraFactor>) -> Self {
+        let mut gagerr = Self::new();
+        gagerr.data = data;
+        gagerr.extra_factors = extra_factors;
+        gagerr
+    }
+
+    pub fn calculate_mut(&mut self) -> Result<&mut Self, GageError> {
+        check_measurements(&self.data)?;
+        self.n_total = self.data.len() as u32;
+        self.mean = self.data.iter().map(|d| d.value).sum::<f64>() / self.n_total as f64;
+        self.tolerance = self.spec_limits.tolerance(self.mean);
+
+        let names = self.factor_names();
+        let levels = self.levels();
+        let parents = self.parents();
+        // Nested factors are checked within the levels of their parent
+        for (f, name) in names.iter().enumerate() {
+            if parents[f].is_none() {
+                let distinct: HashSet<&String> = levels.iter().map(|l| &l[f]).collect();
+                check_levels(name, distinct.len(), 2)?;
+            }
+        }
+        let mut cells: HashMap<&Vec<String>, u32> = HashMap::new();
+        for l in levels.iter() {
+            *cells.entry(l).or_default() += 1;
+        }
+        if cells.values().any(|c| Some(c) != cells.values().next()) {
+            return Err(GageError::UnbalancedDesign(
+                "factor level combinations were measured different numbers of times".to_owned(),
+            ));
+        }
+        check_repeats_vary(levels.iter().zip(self.data.iter().map(|d| d.value)))?;
+        let ancestry: Vec<u32> = (0..names.len()).map(|f| ancestry(f, &parents)).collect();
+        check_nested_levels(&names, &levels, &parents, &ancestry)?;
+        let mut terms = self.terms(&names, &ancestry);
+
+        // Terms are ordered so that every term comes after the terms it
+        // contains, which lets SS and DF be found by subtracting the
+        // contributions of those terms from the marginal values.
+        let n_total = self.n_total as f64;
+        for i in 0..terms.len() {
+            let (ss, cells) = marginal_ss(&self.data, &levels, terms[i].mask, self.mean);
+            let (mut ss, mut df) = (ss, cells as u32 - 1);
+            for j in 0..i {
+                if is_subset(terms[j].mask, terms[i].mask) {
+                    ss -= terms[j].ss;
+                    df = df.checked_sub(terms[j].df).ok_or_else(|| {
+                        GageError::UnbalancedDesign(format!(
+                            "not every level combination of {} was measured",
+                            terms[i].source
+                        ))
+                    })?;
+                }
+            }
+            terms[i].ss = ss.max(0.0);
+            terms[i].df = df;
+            terms[i].ms = terms[i].ss / df as f64;
+            terms[i].per_cell = n_total / cells as f64;
+        }
+        self.confounded_terms = terms
+            .iter()
+            .filter(|t| t.df == 0)
+            .map(|t| t.source.clone())
+            .collect();
+        terms.retain(|t| t.df > 0);
+        let ss_total: f64 = self
+            .data
+            .iter()
+            .map(|d| (d.value - self.mean).powi(2))
+            .sum();
+        let df_total = self.n_total - 1;
+        let df_error = df_total - terms.iter().map(|t| t.df).sum::<u32>();
+        if df_error == 0 {
+            return Err(GageError::TooFewLevels {
+                factor: "Trial".to_owned(),
+                found: 1,
+                required: 2,
+            });
+        }
+        let ss_error = (ss_total - terms.iter().map(|t| t.ss).sum::<f64>()).max(0.0);
+        let ms_error = ss_error / df_error as f64;
+
+        self.anova = terms
+            .iter()
+            .map(|t| anova_row(t, &terms, ms_error, df_error))
+            .collect();
+        self.anova.push(AnovaRow {
+            source: "Repeatability".to_owned(),
+            df: df_error,
+            ss: ss_error,
+            ms: Some(ms_error),
+            f: None,
+            df_error: None,
+            p: None,
+        });
+        self.anova.push(AnovaRow {
+            source: "Total".to_owned(),
+            df: df_total,
+            ss: ss_total,
+            ms: None,
+            f: None,
+            df_error: None,
+            p: None,
+        });
+
+        // Variance components from the expected mean squares, largest terms
+        // first since each term's EMS includes every term containing it. Each
+        // is kept as a linear combination of the mean squares, the last one
+        // being repeatability, for its confidence interval.
+        let mut coefs = vec![Vec::new(); terms.len()];
+        for i in (0..terms.len()).rev() {
+            let mut coef = vec![0.0; terms.len() + 1];
+            coef[i] = 1.0;
+            coef[terms.len()] = -1.0;
+            for j in (i + 1)..terms.len() {
+                if is_subset(terms[i].mask, terms[j].mask) {
+                    for (c, cj) in coef.iter_mut().zip(coefs[j].iter()) {
+                        *c -= terms[j].per_cell * cj;
+                    }
+                }
+            }
+            coefs[i] = coef.iter().map(|c| c / terms[i].per_cell).collect();
+        }
+        let ms: Vec<f64> = terms.iter().map(|t| t.ms).chain([ms_error]).collect();
+        let varcomps: Vec<f64> = coefs
+            .iter()
+            .map(|coef| coef.iter().zip(ms.iter()).map(|(c, ms)| c * ms).sum())
+            .collect();
+
+        // Negative estimates are only truncated once all of them are solved,
+        // so that the lower order terms are not biased by the truncation
+        let mut truncated = Vec::new();
+        let varcomps: Vec<f64> = terms
+            .iter()
+            .zip(varcomps)
+            .enumerate()
+            .map(|(i, (t, v))| {
+                let source = if i == 0 { "Part-To-Part" } else { &t.source };
+                truncate_varcomp(source, v, &mut truncated)
+            })
+            .collect();
+        self.truncated_varcomps = truncated;
+        self.varcomp_repeatability = ms_error;
+        self.varcomp_part = varcomps[0];
+        self.varcomp_terms = terms
+            .iter()
+            .zip(varcomps.iter())
+            .skip(1)
+            .map(|(t, v)| (t.source.clone(), *v))
+            .collect();
+        self.varcomp_reproducibility = self.varcomp_terms.iter().map(|(_, v)| v).sum();
+        self.varcomp_total_gagerr = self.varcomp_repeatability + self.varcomp_reproducibility;
+        self.varcomp_total = self.varcomp_total_gagerr + self.varcomp_part;
+        self.ndc = ndc(self.varcomp_part.sqrt(), self.varcomp_total_gagerr.sqrt());
+        self.stddev_part_process = self
+            .historical_stddev
+            .map(|h| process_stddev_part(h, self.varcomp_total_gagerr.sqrt()));
+        (self.varcomp_intervals, self.ndc_interval) =
+            self.varcomp_intervals(&terms, &coefs, ms_error, df_error);
+        if !self.varcomp_total.is_finite() {
+            return Err(GageError::NonFinite("the variance components".to_owned()));
+        }
+        Ok(self)
+    }
+
+    pub fn calculate(mut self) -> Result<Self, GageError> {
+        self.calculate_mut()?;
+        Ok(self)
+    }
+
+    /// Variance components in report order: total gage R&R, repeatability,
+    /// reproducibility and its terms, part-to-part and total.
+    pub fn varcomp_rows(&self) -> Vec<VarCompRow> {
+        let row = |source: &str, varcomp: f64, indent: bool| VarCompRow {
+            source: source.to_owned(),
+            varcomp,
+            stddev: varcomp.sqrt(),
+            indent,
+            intervals: None,
+        };
+        let mut rows = vec![
+            row("Total Gage R&R", self.varcomp_total_gagerr, false),
+            row("Repeatability", self.varcomp_repeatability, false),
+            row("Reproducibility", self.varcomp_reproducibility, false),
+        ];
+        for (source, varcomp) in self.varcomp_terms.iter() {
+            rows.push(row(source, *varcomp, true));
+        }
+        rows.push(row("Part-To-Part", self.varcomp_part, false));
+        rows.push(row("Total Variation", self.varcomp_total, false));
+        for (row, intervals) in rows.iter_mut().zip(self.varcomp_intervals.iter()) {
+            row.intervals = Some(*intervals);
+        }
+        rows
+    }
+
+    /// Intervals of the variance components in the order of `varcomp_rows`,
+    /// from the mean square combinations `coefs` of the terms, and the ndc
+    /// range they imply.
+    fn varcomp_intervals(
+        &self,
+        terms: &[Term],
+        coefs: &[Vec<f64>],
+        ms_error: f64,
+        df_error: u32,
+    ) -> (Vec<VarCompRowIntervals>, Option<Interval>) {
+        let repeatability = MeanSquareTerm::new("Repeatability", 1.0, ms_error, df_error);
+        let combination = |coef: &[f64]| {
+            let mut combination: Vec<MeanSquareTerm> = terms
+                .iter()
+                .zip(coef.iter())
+                .filter(|(_, c)| **c != 0.0)
+                .map(|(t, c)| MeanSquareTerm::new(&t.source, *c, t.ms, t.df))
+                .collect();
+            combination.push(MeanSquareTerm {
+                coef: coef[terms.len()],
+                ..repeatability
+            });
+            combination.retain(|t| t.coef != 0.0);
+            combination
+        };
+        let components: Vec<Vec<MeanSquareTerm>> = coefs.iter().map(|c| combination(c)).collect();
+        let reproducibility = components[1..]
+            .iter()
+            .fold(Vec::new(), |sum, c| combine(&sum, c));
+        let total_gagerr = combine(&[repeatability], &reproducibility);
+        let total = combine(&total_gagerr, &components[0]);
+
+        let confidence = self.confidence;
+        let row = |terms: &[MeanSquareTerm]| VarCompRowIntervals {
+            varcomp: mls_interval(terms, confidence),
+            contribution: Some(mls_ratio_interval(terms, &total, confidence)),
+        };
+        let mut intervals = vec![
+            row(&total_gagerr),
+            VarCompRowIntervals {
+                varcomp: chi_square_interval(ms_error, df_error, confidence),
+                ..row(&[repeatability])
+            },
+            row(&reproducibility),
+        ];
+        intervals.extend(components[1..].iter().map(|c| row(c)));
+        intervals.push(row(&components[0]));
+        intervals.push(VarCompRowIntervals {
+            varcomp: mls_interval(&total, confidence),
+            contribution: None,
+        });
+        // ndc is a ratio, so pair the opposite limits of part and gage
+        let (part, gagerr) = (intervals[intervals.len() - 2].varcomp, intervals[0].varcomp);
+        let ndc = Interval {
+            lower: (1.41 * (part.lower / gagerr.upper).sqrt()).floor(),
+            upper: (1.41 * (part.upper / gagerr.lower).sqrt()).floor(),
+        };
+        (intervals, Some(ndc))
+    }
+
+    fn factor_names(&self) -> Vec<String> {
+        let mut names = vec!["Part".to_owned(), "Operator".to_owned()];
+        names.extend(self.extra_factors.iter().map(|f| f.name.clone()));
+        names
+    }
+
+    // Level of every factor for every observation
+    fn levels(&self) -> Vec<Vec<String>> {
+        self.data
+            .iter()
+            .map(|d| {
+                let mut levels = vec![d.part.clone(), d.operator.to_string()];
+                for factor in self.extra_factors.iter() {
+                    levels.push(d.factors.get(&factor.name).cloned().unwrap_or_default());
+                }
+                levels
+            })
+            .collect()
+    }
+
+    // Index of the factor each factor is nested in. A factor nested in one
+    // that is not part of the study is treated as crossed.
+    fn parents(&self) -> Vec<Option<usize>> {
+        let names = self.factor_names();
+        let mut parents = vec![None, None];
+        for factor in self.extra_factors.iter() {
+            parents.push(match &factor.role {
+                FactorRole::Crossed => None,
+                FactorRole::NestedIn(parent) => {
+                    names.iter().position(|n| n == parent && *n != factor.name)
+                }
+            });
+        }
+        parents
+    }
+
+    fn terms(&self, names: &[String], ancestry: &[u32]) -> Vec<Term> {
+        let n = names.len();
+        let mut terms: Vec<Term> = Vec::new();
+        let mut push = |mask: u32, live: &[usize]| {
+            if terms.iter().any(|t| t.mask == mask) {
+                return;
+            }
+            let mut source = live
+                .iter()
+                .map(|&f| names[f].as_str())
+                .collect::<Vec<_>>()
+                .join(" * ");
+            let outer: Vec<&str> = (0..n)
+                .filter(|f| mask & (1 << f) != 0 && !live.contains(f))
+                .map(|f| names[f].as_str())
+                .collect();
+            if !outer.is_empty() {
+                source = format!("{} ({})", source, outer.join(" * "));
+            }
+            terms.push(Term {
+                mask,
+                source,
+                df: 0,
+                ss: 0.0,
+                ms: 0.0,
+                per_cell: 0.0,
+            });
+        };
+        for (f, mask) in ancestry.iter().enumerate() {
+            push(*mask, &[f]);
+        }
+        for f in 0..n {
+            for g in (f + 1)..n {
+                // A factor cannot interact with a factor it is nested in
+                let related = ancestry[f] & (1 << g) != 0 || ancestry[g] & (1 << f) != 0;
+                if !related {
+                    push(ancestry[f] | ancestry[g], &[f, g]);
+                }
+            }
+        }
+        terms.sort_by_key(|t| t.mask.count_ones());
+        terms
+    }
+}
+
+// Bit mask of a factor and every factor it is nested in.
+fn ancestry(factor: usize, parents: &[Option<usize>]) -> u32 {
+    let mut mask = 1 << factor;
+    let mut current = factor;
+    // Bounded by the number of factors so a circular nesting cannot loop.
+    for _ in 0..parents.len() {
+        match parents[current] {
+            Some(parent) if mask & (1 << parent) == 0 => {
+                mask |= 1 << parent;
+                current = parent;
+            }
+            _ => break,
+        }
+    }
+    mask
+}
+
+/// Checks that a nested factor has at least two levels within every level of
+/// the factors it is nested in, or a single level within all of them. In the
+/// latter case its terms have no degrees of freedom and are left out of the
+/// model, but a mix of the two cannot be estimated from the balanced design.
+fn check_nested_levels(
+    names: &[String],
+    levels: &[Vec<String>],
+    parents: &[Option<usize>],
+    ancestry: &[u32],
+) -> Result<(), GageError> {
+    for (f, parent) in parents.iter().enumerate() {
+        let Some(parent) = parent else {
+            continue;
+        };
+        // Levels of the factor within every level combination of its
+        // ancestors, with the level of its immediate parent
+        let outer = ancestry[f] & !(1 << f);
+        let mut nested: HashMap<Vec<&str>, (&str, HashSet<&str>)> = HashMap::new();
+        for l in levels.iter() {
+            let key = (0..names.len())
+                .filter(|g| outer & (1 << g) != 0)
+                .map(|g| l[g].as_str())
+                .collect();
+            let (_, found) = nested
+                .entry(key)
+                .or_insert_with(|| (l[*parent].as_str(), HashSet::new()));
+            found.insert(l[f].as_str());
+        }
+        if nested.values().all(|(_, found)| found.len() == 1) {
+            continue;
+        }
+        let fewest = nested
+            .iter()
+            .min_by(|(a, (_, x)), (b, (_, y))| x.len().cmp(&y.len()).then_with(|| a.cmp(b)));
+        if let Some((_, (parent_level, found))) = fewest {
+            check_levels(
+                &format!("{} within {} {}", names[f], names[*parent], parent_level),
+                found.len(),
+                2,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn is_subset(inner: u32, outer: u32) -> bool {
+    inner != outer && inner & outer == inner
+}
+
+// Sum of squares of the level combination means of the factors in `mask`
+// around the grand mean, and the number of combinations.
+fn marginal_ss(
+    data: &[GageStudyData],
+    levels: &[Vec<String>],
+    mask: u32,
+    mean: f64,
+) -> (f64, usize) {
+    let mut cells: HashMap<Vec<&str>, (f64, u32)> = HashMap::new();
+    for (d, l) in data.iter().zip(levels.iter()) {
+        let key = l
+            .iter()
+            .enumerate()
+            .filter(|(f, _)| mask & (1 << f) != 0)
+            .map(|(_, level)| level.as_str())
+            .collect();
+        let cell = cells.entry(key).or_insert((0.0, 0));
+        cell.0 += d.value;
+        cell.1 += 1;
+    }
+    let ss = cells
+        .values()
+        .map(|(sum, count)| *count as f64 * (sum / *count as f64 - mean).powi(2))
+        .sum();
+    (ss, cells.len())
+}
+
+// F test of a term against the mean square with the same expectation minus
+// the term's own component. When no single mean square fits, the
+// denominator is built from several and its DF found by Satterthwaite.
+fn anova_row(term: &Term, terms: &[Term], ms_error: f64, df_error: u32) -> AnovaRow {
+    let containing: Vec<&Term> = terms
+        .iter()
+        .filter(|t| is_subset(term.mask, t.mask))
+        .collect();
+    let mut coefs = vec![0.0; containing.len()];
+    for i in 0..containing.len() {
+        let covered: f64 = (0..i)
+            .filter(|&j| is_subset(containing[j].mask, containing[i].mask))
+            .map(|j| coefs[j])
+            .sum();
+        coefs[i] = 1.0 - covered;
+    }
+    let coef_error = 1.0 - coefs.iter().sum::<f64>();
+
+    let mut denominator = coef_error * ms_error;
+    let mut df_sum = if df_error > 0 {
+        (coef_error * ms_error).powi(2) / df_error as f64
+    } else {
+        0.0
+    };
+    for (t, c) in containing.iter().zip(coefs.iter()) {
+        denominator += c * t.ms;
+        if *c != 0.0 {
+            df_sum += (c * t.ms).powi(2) / t.df as f64;
+        }
+    }
+    let df_den = denominator.powi(2) / df_sum;
+    let testable = term.df > 0 && denominator > 0.0 && df_den.is_finite();
+    let f = testable.then(|| term.ms / denominator);
+    AnovaRow {
+        source: term.source.clone(),
+        df: term.df,
+        ss: term.ss,
+        ms: Some(term.ms),
+        f,
+        df_error: testable.then_some(df_den),
+        p: f.map(|f| stats::f_sf_approx(f, term.df as f64, df_den)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gage_study_data_set::GageStudyDataSet;
+    use std::collections::BTreeMap;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    /// Three parts, two operators and two fixtures, every combination
+    /// measured twice. Fixture labels are the same for both operators.
+    fn study_data(fixture: impl Fn(usize, usize) -> usize) -> Vec<GageStudyData> {
+        let mut data = Vec::new();
+        for p in 0..3 {
+            for o in 0..2 {
+                for f in 0..2 {
+                    for r in 0..2 {
+                        let value = 10.0
+                            + 0.8 * p as f64
+                            + 0.15 * o as f64
+                            + 0.1 * f as f64
+                            + 0.05 * ((p * 3 + o * 5 + f * 7) % 4) as f64
+                            + 0.03 * ((p + 2 * o + 3 * f + 5 * r) % 3) as f64;
+                        let factors =
+                            BTreeMap::from([("Fixture".to_owned(), fixture(o, f).to_string())]);
+                        data.push(
+                            GageStudyData::new(
+                                &(p + 1).to_string(),
+                                o as u32 + 1,
+                                r as u32 + 1,
+                                value,
+                            )
+                            .with_factors(factors),
+                        );
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    fn calculate(data: Vec<GageStudyData>, role: FactorRole) -> ExpandedGageStudyDataSet {
+        ExpandedGageStudyDataSet::new_with_data(data, vec![ExtraFactor::new("Fixture", role)])
+            .calculate()
+            .unwrap()
+    }
+
+    fn varcomp(study: &ExpandedGageStudyDataSet, source: &str) -> f64 {
+        study
+            .varcomp_terms
+            .iter()
+            .find(|(s, _)| s == source)
+            .map(|(_, v)| *v)
+            .unwrap_or_else(|| panic!("no {source} term"))
+    }
+
+    #[test]
+    fn crossed_factor_matches_reference_values() {
+        let study = calculate(study_data(|_, f| f + 1), FactorRole::Crossed);
+        let ss: Vec<(&str, u32, f64)> = study
+            .anova
+            .iter()
+            .map(|r| (r.source.as_str(), r.df, r.ss))
+            .collect();
+        let expected = [
+            ("Part", 2, 10.792233),
+            ("Operator", 1, 0.106667),
+            ("Fixture", 1, 0.081667),
+            ("Part * Operator", 2, 0.004033),
+            ("Part * Fixture", 2, 0.013333),
+            ("Operator * Fixture", 1, 0.0),
+            ("Repeatability", 14, 0.0508),
+        ];
+        for ((source, df, ss), (e_source, e_df, e_ss)) in ss.iter().zip(expected) {
+            assert_eq!((*source, *df), (e_source, e_df));
+            assert_close(*ss, e_ss, 1e-6);
+        }
+        assert_close(study.varcomp_repeatability, 0.003629, 1e-6);
+        assert_close(study.varcomp_part, 0.673883, 1e-6);
+        assert_close(varcomp(&study, "Operator"), 0.009023, 1e-6);
+        assert_close(varcomp(&study, "Fixture"), 0.006552, 1e-6);
+        assert_close(varcomp(&study, "Part * Fixture"), 0.000760, 1e-6);
+        assert_eq!(varcomp(&study, "Part * Operator"), 0.0);
+        assert_eq!(
+            study.truncated_varcomps,
+            vec![
+                "Part * Operator".to_owned(),
+                "Operator * Fixture".to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_factor_matches_reference_values() {
+        let study = calculate(
+            study_data(|_, f| f + 1),
+            FactorRole::NestedIn("Operator".to_owned()),
+        );
+        let sources: Vec<&str> = study.anova.iter().map(|r| r.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            [
+                "Part",
+                "Operator",
+                "Fixture (Operator)",
+                "Part * Operator",
+                "Part * Fixture (Operator)",
+                "Repeatability",
+                "Total"
+            ]
+        );
+        assert_close(study.anova[2].ss, 0.081667, 1e-6);
+        assert_eq!(study.anova[2].df, 2);
+        assert_close(study.anova[4].ss, 0.053333, 1e-6);
+        assert_eq!(study.anova[4].df, 4);
+        assert_close(study.varcomp_repeatability, 0.0009, 1e-6);
+        assert_close(study.varcomp_part, 0.674262, 1e-6);
+        assert_close(varcomp(&study, "Operator"), 0.006429, 1e-6);
+        assert_close(varcomp(&study, "Fixture (Operator)"), 0.004583, 1e-6);
+        assert_close(varcomp(&study, "Part * Fixture (Operator)"), 0.006217, 1e-6);
+        assert_eq!(varcomp(&study, "Part * Operator"), 0.0);
+    }
+
+    #[test]
+    fn nested_factor_with_single_level_is_confounded_with_its_parent() {
+        // Every operator uses their own fixture, so fixture and operator
+        // cannot be told apart
+        let data: Vec<GageStudyData> = study_data(|o, _| o + 1)
+            .into_iter()
+            .filter(|d| d.trial == 1)
+            .collect();
+        let study = calculate(data, FactorRole::NestedIn("Operator".to_owned()));
+        assert_eq!(
+            study.confounded_terms,
+            vec![
+                "Fixture (Operator)".to_owned(),
+                "Part * Fixture (Operator)".to_owned()
+            ]
+        );
+        let sources: Vec<&str> = study.anova.iter().map(|r| r.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            [
+                "Part",
+                "Operator",
+                "Part * Operator",
+                "Repeatability",
+                "Total"
+            ]
+        );
+        assert!(study.varcomp_total.is_finite());
+        assert!(study
+            .varcomp_rows()
+            .iter()
+            .all(|r| r.varcomp.is_finite() && r.stddev.is_finite()));
+    }
+
+    #[test]
+    fn nested_factor_with_single_level_in_some_parents_is_named() {
+        // Operator 1 uses one fixture, operator 2 two
+        let data = study_data(|_, f| f + 1)
+            .into_iter()
+            .filter(|d| d.operator == 2 || d.factors["Fixture"] == "1")
+            .collect();
+        let error = ExpandedGageStudyDataSet::new_with_data(
+            data,
+            vec![ExtraFactor::new(
+                "Fixture",
+                FactorRole::NestedIn("Operator".to_owned()),
+            )],
+        )
+        .calculate()
+        .err();
+        assert_eq!(
+            error,
+            Some(GageError::TooFewLevels {
+                factor: "Fixture within Operator 1".to_owned(),
+                found: 1,
+                required: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn intervals_match_crossed_study_without_extra_factors() {
+        // Trials numbered within every part and operator
+        let mut data = study_data(|_, f| f + 1);
+        for (i, d) in data.iter_mut().enumerate() {
+            d.trial = i as u32 % 4 + 1;
+        }
+        let expanded = ExpandedGageStudyDataSet::new_with_data(data.clone(), Vec::new())
+            .calculate()
+            .unwrap();
+        let mut crossed = GageStudyDataSet::new_with_data(data);
+        crossed.pool_interaction = false;
+        let crossed = crossed.calculate().unwrap();
+        let ci = crossed.varcomp_intervals.unwrap();
+
+        let rows = expanded.varcomp_rows();
+        let sources: Vec<&str> = rows.iter().map(|r| r.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            [
+                "Total Gage R&R",
+                "Repeatability",
+                "Reproducibility",
+                "Operator",
+                "Part * Operator",
+                "Part-To-Part",
+                "Total Variation"
+            ]
+        );
+        let expected = [
+            (ci.total_gagerr, Some(ci.contribution.total_gagerr)),
+            (ci.repeatability, Some(ci.contribution.repeatability)),
+            (ci.reproducibility.unwrap(), ci.contribution.reproducibility),
+            (ci.operator.unwrap(), ci.contribution.operator),
+            (ci.part_operator.unwrap(), ci.contribution.part_operator),
+            (ci.part, Some(ci.contribution.part)),
+            (ci.total, None),
+        ];
+        let assert_interval = |actual: Interval, expected: Interval| {
+            assert_close(actual.lower, expected.lower, 1e-9);
+            assert_close(actual.upper, expected.upper, 1e-9);
+        };
+        for (row, (varcomp, contribution)) in rows.iter().zip(expected) {
+            let intervals = row.intervals.unwrap();
+            assert_interval(intervals.varcomp, varcomp);
+            match (intervals.contribution, contribution) {
+                (Some(actual), Some(expected)) => assert_interval(actual, expected),
+                (actual, expected) => assert_eq!(actual, expected),
+            }
+        }
+        assert_eq!(expanded.ndc_interval, Some(ci.ndc));
+    }
+
+    #[test]
+    fn intervals_contain_the_estimates() {
+        let study = calculate(
+            study_data(|_, f| f + 1),
+            FactorRole::NestedIn("Operator".to_owned()),
+        );
+        for row in study.varcomp_rows() {
+            let intervals = row.intervals.unwrap();
+            assert!(
+                intervals.varcomp.lower <= row.varcomp && row.varcomp <= intervals.varcomp.upper,
+                "{} outside its interval",
+                row.source
+            );
+            if let Some(share) = intervals.contribution {
+                let estimate = row.varcomp / study.varcomp_total;
+                assert!(share.lower <= estimate && estimate <= share.upper);
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::gage_study_data::GageStudyData;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Reasons a gage study cannot be evaluated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GageError {
+    /// There are no measurements.
+    EmptyData,
+    /// The measurements do not have the structure the analysis relies on.
+    UnbalancedDesign(String),
+    /// A factor has fewer levels than its effect needs to be estimated.
+    TooFewLevels {
+        factor: String,
+        found: usize,
+        required: usize,
+    },
+    /// A factor has more levels than the analysis has constants for.
+    TooManyLevels {
+        factor: String,
+        found: usize,
+        allowed: usize,
+    },
+    /// Repeated measurements are all identical, so there is no error term.
+    ZeroVariance,
+    /// A measurement or result is NaN or infinite.
+    NonFinite(String),
+    /// An iterative estimate did not settle within its iteration limit.
+    NotConverged(String),
+}
+
+impl fmt::Display for GageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GageError::EmptyData => write!(f, "There are no measurements to analyze."),
+            GageError::UnbalancedDesign(reason) => write!(f, "Unbalanced design: {reason}."),
+            GageError::TooFewLevels {
+                factor,
+                found,
+                required,
+            } => write!(
+                f,
+                "{factor} has {found} level(s), at least {required} are needed."
+            ),
+            GageError::TooManyLevels {
+                factor,
+                found,
+                allowed,
+            } => write!(
+                f,
+                "{factor} has {found} level(s), at most {allowed} are supported."
+            ),
+            GageError::ZeroVariance => write!(
+                f,
+                "Repeated measurements are all identical, so the repeatability cannot be \
+                 estimated. The gage resolution may be too coarse for these parts."
+            ),
+            GageError::NonFinite(what) => write!(f, "Non-finite value in {what}."),
+            GageError::NotConverged(what) => write!(f, "{what} did not converge."),
+        }
+    }
+}
+
+impl std::error::Error for GageError {}
+
+/// Checks that there are measurements and that all of them are finite.
+pub fn check_measurements(data: &[GageStudyData]) -> Result<(), GageError> {
+    if data.is_empty() {
+        return Err(GageError::EmptyData);
+    }
+    match data.iter().find(|d| !d.value.is_finite()) {
+        Some(d) => Err(GageError::NonFinite(format!(
+            "the measurement of part {} by operator {} in trial {}",
+            d.part, d.operator, d.trial
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the measurements vary within at least one cell, given the
+/// cell and value of every measurement.
+pub fn check_repeats_vary<K: Hash + Eq>(
+    measurements: impl Iterator<Item = (K, f64)>,
+) -> Result<(), GageError> {
+    let mut first: HashMap<K, f64> = HashMap::new();
+    for (cell, value) in measurements {
+        if *first.entry(cell).or_insert(value) != value {
+            return Ok(());
+        }
+    }
+    Err(GageError::ZeroVariance)
+}
+
+/// Checks that a factor has at least `required` levels.
+pub fn check_levels(factor: &str, found: usize, required: usize) -> Result<(), GageError> {
+    if found < required {
+        return Err(GageError::TooFewLevels {
+            factor: factor.to_owned(),
+            found,
+            required,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GageStudyData {
+    pub part: String,
+    pub operator: u32,
+    pub trial: u32,
+    pub value: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reference: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rating: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub standard: Option<String>,
+    /// Levels of any additional factors (fixture, gage, lab, ...), keyed by
+    /// factor name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub factors: BTreeMap<String, String>,
+}
+
+impl GageStudyData {
+    pub fn new(part: &str, operator: u32, trial: u32, value: f64) -> Self {
+        Self {
+            part: part.to_owned(),
+            operator,
+            trial,
+            value,
+            reference: None,
+            rating: None,
+            standard: None,
+            factors: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_reference(mut self, reference: Option<f64>) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    pub fn with_rating(mut self, rating: Option<String>, standard: Option<String>) -> Self {
+        self.rating = rating;
+        self.standard = standard;
+        self
+    }
+
+    pub fn with_factors(mut self, factors: BTreeMap<String, String>) -> Self {
+        self.factors = factors;
+        self
+    }
+}
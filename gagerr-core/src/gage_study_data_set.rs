@@ -0,0 +1,1238 @@
+use crate::aggregates::Aggregates;
+use crate::confidence_interval::{MeanSquareTerm, VarCompIntervals};
+use crate::constants;
+use crate::gage_error::{check_levels, check_measurements, GageError};
+use crate::gage_study_data::GageStudyData;
+use crate::reml::{reml, Cholesky};
+use crate::spec_limits::SpecLimits;
+use crate::stats;
+use crate::study_type::VarCompMethod;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// ANOVA table for the model without the part/operator interaction, produced
+/// when the interaction term is pooled into repeatability.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ReducedAnova {
+    pub df_part: u32,
+    pub df_operator: u32,
+    pub df_repeatability: u32,
+    pub df_total: u32,
+    pub ss_part: f64,
+    pub ss_operator: f64,
+    pub ss_repeatability: f64,
+    pub ss_total: f64,
+    pub ms_part: f64,
+    pub ms_operator: Option<f64>,
+    pub ms_repeatability: f64,
+    pub f_part: f64,
+    pub f_operator: Option<f64>,
+    pub p_part: f64,
+    pub p_operator: Option<f64>,
+}
+
+/// AIAG Average and Range method results. All variations are standard
+/// deviations.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RangeMethod {
+    /// Average of the part/operator cell ranges
+    pub r_bar: f64,
+    /// Difference between the largest and smallest operator averages
+    pub x_diff: f64,
+    /// Range of the part averages
+    pub r_part: f64,
+    pub k1: f64,
+    pub k2: Option<f64>,
+    pub k3: f64,
+    /// Equipment variation (repeatability)
+    pub ev: f64,
+    /// Appraiser variation (reproducibility)
+    pub av: f64,
+    pub grr: f64,
+    /// Part variation
+    pub pv: f64,
+    /// Total variation
+    pub tv: f64,
+    /// Number of distinct categories
+    pub ndc: u32,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GageStudyDataSet {
+    pub data: Vec<GageStudyData>,
+    /// Sums and counts of `data` by part, operator and cell
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub aggregates: Aggregates,
+    pub parts: HashSet<String>,
+    pub operators: HashSet<u32>,
+    pub trials: HashSet<u32>,
+    pub study_variation: f64,
+    pub spec_limits: SpecLimits,
+    /// Tolerance from the spec limits, if any were given
+    pub tolerance: Option<f64>,
+    /// Known standard deviation of the process, for parts that do not cover
+    /// the process spread
+    pub historical_stddev: Option<f64>,
+    /// Confidence level of `varcomp_intervals`
+    pub confidence: f64,
+    pub pool_interaction: bool,
+    pub alpha_to_remove: f64,
+    /// Requested variance component method; REML is used regardless when the
+    /// design is unbalanced
+    pub varcomp_method: VarCompMethod,
+    /// Method the variance components were actually estimated with: ANOVA
+    /// when REML was requested for a balanced design but did not converge
+    pub varcomp_method_used: VarCompMethod,
+    /// Whether every part was measured by every operator equally often
+    pub balanced: bool,
+    pub mean: f64,
+    pub n_part: u32,
+    pub n_operator: u32,
+    /// Measurements of every part by every operator, whatever the trials are
+    /// labelled. The average when the design is unbalanced.
+    pub n_trial: u32,
+    pub n_total: u32,
+    pub df_part: u32,
+    pub df_operator: u32,
+    pub df_part_operator: u32,
+    pub df_repeatability: u32,
+    pub df_total: u32,
+    pub ss_part: f64,
+    pub ss_operator: f64,
+    pub ss_part_operator: f64,
+    pub ss_repeatability: f64,
+    pub ss_total: f64,
+    pub ms_part: f64,
+    pub ms_operator: Option<f64>,
+    pub ms_part_operator: Option<f64>,
+    pub ms_repeatability: f64,
+    pub f_part: f64,
+    pub f_operator: Option<f64>,
+    pub f_part_operator: Option<f64>,
+    pub varcomp_part: f64,
+    pub varcomp_operator: Option<f64>,
+    pub varcomp_part_operator: Option<f64>,
+    pub varcomp_repeatability: f64,
+    pub varcomp_reproducibility: Option<f64>,
+    pub varcomp_total_gagerr: f64,
+    pub varcomp_total: f64,
+    /// Sources whose negative estimate was set to zero
+    pub truncated_varcomps: Vec<String>,
+    pub stddev_part: f64,
+    pub stddev_operator: Option<f64>,
+    pub stddev_part_operator: Option<f64>,
+    pub stddev_repeatability: f64,
+    pub stddev_reproducibility: Option<f64>,
+    pub stddev_total_gagerr: f64,
+    pub stddev_total: f64,
+    /// Number of distinct categories, AIAG considers 5 or more acceptable
+    pub ndc: u32,
+    /// Part-to-part standard deviation implied by the historical one
+    pub stddev_part_process: Option<f64>,
+    pub p_part: f64,
+    pub p_operator: Option<f64>,
+    pub p_part_operator: Option<f64>,
+    pub reduced_anova: Option<ReducedAnova>,
+    pub range_method: Option<RangeMethod>,
+    pub varcomp_intervals: Option<VarCompIntervals>,
+}
+
+impl Default for GageStudyDataSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GageStudyDataSet {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::<GageStudyData>::new(),
+            aggregates: Aggregates::default(),
+            parts: HashSet::new(),
+            operators: HashSet::new(),
+            trials: HashSet::new(),
+            study_variation: 5.15,
+            spec_limits: SpecLimits::default(),
+            tolerance: None,
+            historical_stddev: None,
+            confidence: 0.95,
+            pool_interaction: true,
+            alpha_to_remove: 0.25,
+            varcomp_method: VarCompMethod::Anova,
+            varcomp_method_used: VarCompMethod::Anova,
+            balanced: true,
+            mean: 0.0,
+            n_part: 0,
+            n_operator: 0,
+            n_trial: 0,
+            n_total: 0,
+            df_part: 0,
+            df_operator: 0,
+            df_part_operator: 0,
+            df_repeatability: 0,
+            df_total: 0,
+            ss_part: 0.0,
+            ss_operator: 0.0,
+            ss_part_operator: 0.0,
+            ss_repeatability: 0.0,
+            ss_total: 0.0,
+            ms_part: 0.0,
+            ms_operator: None,
+            ms_part_operator: None,
+            ms_repeatability: 0.0,
+            f_part: 0.0,
+            f_operator: None,
+            f_part_operator: None,
+            p_part: 0.0,
+            p_operator: None,
+            p_part_operator: None,
+            reduced_anova: None,
+            range_method: None,
+            varcomp_part: 0.0,
+            varcomp_operator: None,
+            varcomp_part_operator: None,
+            varcomp_repeatability: 0.0,
+            varcomp_reproducibility: None,
+            varcomp_total_gagerr: 0.0,
+            varcomp_total: 0.0,
+            truncated_varcomps: Vec::new(),
+            stddev_part: 0.0,
+            stddev_operator: None,
+            stddev_part_operator: None,
+            stddev_repeatability: 0.0,
+            stddev_reproducibility: None,
+            stddev_total_gagerr: 0.0,
+            stddev_total: 0.0,
+            ndc: 0,
+            stddev_part_process: None,
+            varcomp_intervals: None,
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>) -> Self {
+        let mut gagerr = Self::new();
+        gagerr.data = data;
+        gagerr
+    }
+
+    pub fn calculate_mut(&mut self) -> Result<&mut Self, GageError> {
+        check_measurements(&self.data)?;
+        self.aggregates = Aggregates::new(&self.data);
+        self.mean = self.mean();
+        self.tolerance = self.spec_limits.tolerance(self.mean);
+        self.parts = self.unique_parts();
+        self.n_part = self.parts.len() as u32;
+        self.operators = self.unique_operators();
+        self.n_operator = self.operators.len() as u32;
+        self.trials = self.unique_trials();
+        self.n_total = self.data.len() as u32;
+        check_levels("Part", self.parts.len(), 2)?;
+        self.check_connected()?;
+        if self.n_total as usize == self.aggregates.cells.len() {
+            return Err(GageError::TooFewLevels {
+                factor: "Trial".to_owned(),
+                found: 1,
+                required: 2,
+            });
+        }
+        if self.aggregates.cell.iter().all(|c| c.range() == 0.0) {
+            return Err(GageError::ZeroVariance);
+        }
+        self.balanced = self.is_balanced();
+        // Trial labels need not be the same in every cell, so the replicates
+        // are counted per cell rather than taken from the labels
+        let n_cell = self.aggregates.cells.len() as u32;
+        self.n_trial = (self.n_total as f64 / n_cell as f64).round() as u32;
+        self.df_part = self.n_part - 1;
+        self.df_operator = self.n_operator - 1;
+        // Cells that were not measured take their degrees of freedom away
+        // from the interaction
+        self.df_part_operator = n_cell + 1 - self.n_part - self.n_operator;
+        self.df_repeatability = self.n_total - n_cell;
+        self.df_total = self.n_total - 1;
+        self.ss_part = self.ss_part();
+        self.ss_operator = self.ss_operator();
+        self.ss_total = self.ss_total();
+        self.ss_repeatability = self.ss_repeatability();
+        self.ss_part_operator = self.ss_part_operator();
+        if !self.balanced {
+            self.adjust_ss_mut()?;
+        }
+        self.ms_part = self.ms_part();
+        self.ms_operator = self.ms_operator();
+        self.ms_repeatability = self.ms_repeatability();
+        self.ms_part_operator = self.ms_part_operator();
+        self.f_part = self.f_part();
+        self.f_operator = self.f_operator();
+        self.f_part_operator = self.f_part_operator();
+        self.p_part = self.p_part();
+        self.p_operator = self.p_operator();
+        self.p_part_operator = self.p_part_operator();
+        self.reduced_anova = self.reduced_anova();
+        self.varcomp_repeatability = self.varcomp_ms_repeatability();
+        self.varcomp_part_operator = self.varcomp_part_operator();
+        self.varcomp_part = self.varcomp_part();
+        self.varcomp_operator = self.varcomp_operator();
+        self.varcomp_method_used = VarCompMethod::Anova;
+        if !self.balanced || self.varcomp_method == VarCompMethod::Reml {
+            self.reml_mut()?;
+        }
+        self.truncate_varcomps_mut();
+        self.varcomp_reproducibility = self.varcomp_reproducibility();
+        self.varcomp_total_gagerr = self.varcomp_total_gagerr();
+        self.varcomp_total = self.varcomp_total();
+        self.stddev_part = self.varcomp_part.sqrt();
+        self.stddev_operator = self.varcomp_operator.map(f64::sqrt);
+        self.stddev_part_operator = self.varcomp_part_operator.map(f64::sqrt);
+        self.stddev_repeatability = self.varcomp_repeatability.sqrt();
+        self.stddev_reproducibility = self.varcomp_reproducibility.map(f64::sqrt);
+        self.stddev_total_gagerr = self.varcomp_total_gagerr.sqrt();
+        self.stddev_total = self.varcomp_total.sqrt();
+        self.ndc = ndc(self.stddev_part, self.stddev_total_gagerr);
+        self.stddev_part_process = self
+            .historical_stddev
+            .map(|h| process_stddev_part(h, self.stddev_total_gagerr));
+        // The AIAG constants assume every cell has the same number of trials
+        self.range_method = if self.balanced {
+            self.range_method()
+        } else {
+            None
+        };
+        self.varcomp_intervals = match self.varcomp_method_used {
+            VarCompMethod::Anova => self.varcomp_intervals(),
+            VarCompMethod::Reml => None,
+        };
+        if !self.varcomp_total.is_finite() {
+            return Err(GageError::NonFinite("the variance components".to_owned()));
+        }
+        Ok(self)
+    }
+
+    pub fn calculate(mut self) -> Result<Self, GageError> {
+        self.calculate_mut()?;
+        Ok(self)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.aggregates.total.mean()
+    }
+
+    pub fn unique_parts(&self) -> HashSet<String> {
+        self.aggregates.parts.iter().cloned().collect()
+    }
+
+    pub fn unique_operators(&self) -> HashSet<u32> {
+        self.aggregates.operators.iter().copied().collect()
+    }
+
+    pub fn unique_trials(&self) -> HashSet<u32> {
+        let mut trials = HashSet::new();
+        for i in self.data.iter() {
+            trials.insert(i.trial);
+        }
+        trials
+    }
+
+    pub fn ss_total(&self) -> f64 {
+        let mut sum = 0.0;
+        for data in self.data.iter() {
+            sum += (data.value - self.mean).powi(2);
+        }
+        sum
+    }
+
+    /// Sum of square differences between each operator's mean and the
+    /// overall mean, weighted by the operator's number of measurements.
+    pub fn ss_operator(&self) -> f64 {
+        Aggregates::ss_between(&self.aggregates.operator, self.mean)
+    }
+
+    /// Sum of square differences between each part's mean and the overall
+    /// mean, weighted by the part's number of measurements.
+    pub fn ss_part(&self) -> f64 {
+        Aggregates::ss_between(&self.aggregates.part, self.mean)
+    }
+
+    /// Checks that the parts and operators are connected: every two parts
+    /// are linked by operators that measured both, directly or through other
+    /// parts. Parts that were not measured by every operator are fine, but
+    /// the effects of groups of parts and operators that share no
+    /// measurements cannot be told apart.
+    fn check_connected(&self) -> Result<(), GageError> {
+        let aggregates = &self.aggregates;
+        if aggregates.is_complete() {
+            return Ok(());
+        }
+        // Union-find over the parts followed by the operators
+        let n_part = aggregates.parts.len();
+        let mut group: Vec<usize> = (0..n_part + aggregates.operators.len()).collect();
+        fn root(group: &mut [usize], mut i: usize) -> usize {
+            while group[i] != i {
+                group[i] = group[group[i]];
+                i = group[i];
+            }
+            i
+        }
+        for (part, operator) in aggregates.cells.iter() {
+            let (a, b) = (root(&mut group, *part), root(&mut group, n_part + operator));
+            group[a] = b;
+        }
+        let first = root(&mut group, 0);
+        match (1..n_part).find(|p| root(&mut group, *p) != first) {
+            Some(part) => Err(GageError::UnbalancedDesign(format!(
+                "parts {} and {} share no operator, directly or through other parts",
+                aggregates.parts[0], aggregates.parts[part]
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// A design is balanced when every part and operator combination has
+    /// the same, non-zero number of measurements. Anything else, including
+    /// parts that some operators did not measure, is estimated by REML.
+    pub fn is_balanced(&self) -> bool {
+        let cells = &self.aggregates.cell;
+        self.aggregates.is_complete() && cells.iter().all(|c| c.count == cells[0].count)
+    }
+
+    /// Sum of square differences between each value and the mean of its
+    /// part/operator cell, i.e. the variation within repeated trials.
+    fn ss_repeatability(&self) -> f64 {
+        let cells = &self.aggregates.cell;
+        self.data
+            .iter()
+            .zip(self.aggregates.cell_of.iter())
+            .map(|(data, cell)| (data.value - cells[*cell].mean()).powi(2))
+            .sum()
+    }
+
+    /// Whatever is left of the total once the main effects and repeatability
+    /// are accounted for. Zero when there is a single operator. Only valid
+    /// for a balanced design, see `adjust_ss_mut`.
+    pub fn ss_part_operator(&self) -> f64 {
+        if self.n_operator < 2 {
+            return 0.0;
+        }
+        (self.ss_total - (self.ss_part + self.ss_operator + self.ss_repeatability)).max(0.0)
+    }
+
+    /// Replaces the sums of squares of an unbalanced design with adjusted
+    /// ones: each main effect adjusted for the other, and the interaction
+    /// for both. Without balance the sequential sums of squares depend on
+    /// the order of the terms, and the remainder is not an interaction sum
+    /// of squares.
+    fn adjust_ss_mut(&mut self) -> Result<(), GageError> {
+        if self.n_operator < 2 {
+            return Ok(());
+        }
+        let operator = self.ss_operator_adjusted()?;
+        let error_additive = self.ss_total - self.ss_part - operator;
+        self.ss_part_operator = (error_additive - self.ss_repeatability).max(0.0);
+        self.ss_part += operator - self.ss_operator;
+        self.ss_operator = operator;
+        Ok(())
+    }
+
+    /// Operator sum of squares adjusted for parts, `q'C⁻¹q` with `C` and `q`
+    /// the operator normal equations once the part effects are absorbed. The
+    /// last operator is dropped to make `C` invertible.
+    fn ss_operator_adjusted(&self) -> Result<f64, GageError> {
+        let aggregates = &self.aggregates;
+        let n = aggregates.operators.len() - 1;
+        let mut c = vec![vec![0.0; n]; n];
+        let mut q = vec![0.0; n];
+        let mut by_part: Vec<Vec<usize>> = vec![Vec::new(); aggregates.parts.len()];
+        for (cell, (part, _)) in aggregates.cells.iter().enumerate() {
+            by_part[*part].push(cell);
+        }
+        for (cells, part) in by_part.iter().zip(aggregates.part.iter()) {
+            let part_count = part.count as f64;
+            for a in cells.iter() {
+                let (o, tally) = (aggregates.cells[*a].1, &aggregates.cell[*a]);
+                if o == n {
+                    continue;
+                }
+                let count = tally.count as f64;
+                c[o][o] += count;
+                q[o] += tally.sum - count * part.mean();
+                for b in cells.iter() {
+                    let p = aggregates.cells[*b].1;
+                    if p < n {
+                        c[o][p] -= count * aggregates.cell[*b].count as f64 / part_count;
+                    }
+                }
+            }
+        }
+        let c = Cholesky::new(c).ok_or_else(|| {
+            GageError::UnbalancedDesign(
+                "the part and operator effects cannot be told apart".to_owned(),
+            )
+        })?;
+        let solution = c.solve(q.clone());
+        Ok(q.iter().zip(solution.iter()).map(|(q, x)| q * x).sum())
+    }
+
+    // fn df_part(&self) -> u32 {
+    //     self.unique_parts().len() as u32 - 1
+    // }
+
+    // fn df_operator(&self) -> u32 {
+    //     self.unique_operators().len() as u32 - 1
+    // }
+
+    // fn df_part_operator(&self) -> u32 {
+    //     self.df_part() * self.df_operator()
+    // }
+
+    // fn df_repeatability(&self) -> u32 {
+    //     let nparts = self.unique_parts().len() as u32;
+    //     let noperators = self.unique_operators().len() as u32;
+    //     let ntrials = self.unique_trials().len() as u32;
+    //     nparts * noperators * (ntrials - 1)
+    // }
+
+    // fn df_total(&self) -> u32 {
+    //     let nparts = self.unique_parts().len() as u32;
+    //     let noperators = self.unique_operators().len() as u32;
+    //     let ntrials = self.unique_trials().len() as u32;
+    //     (nparts * noperators * ntrials) - 1
+    // }
+
+    fn ms_part(&self) -> f64 {
+        self.ss_part / self.df_part as f64
+    }
+
+    fn ms_operator(&self) -> Option<f64> {
+        if self.df_operator == 0 {
+            return None;
+        }
+        Some(self.ss_operator / self.df_operator as f64)
+    }
+
+    fn ms_part_operator(&self) -> Option<f64> {
+        if self.df_part_operator == 0 {
+            return None;
+        }
+        Some(self.ss_part_operator / self.df_part_operator as f64)
+    }
+
+    fn ms_repeatability(&self) -> f64 {
+        self.ss_repeatability / self.df_repeatability as f64
+    }
+
+    /// Error term for the main effects: the interaction mean square when the
+    /// interaction is in the model, repeatability otherwise.
+    fn ms_error(&self) -> f64 {
+        self.ms_part_operator.unwrap_or(self.ms_repeatability)
+    }
+
+    fn df_error(&self) -> u32 {
+        match self.ms_part_operator {
+            Some(_) => self.df_part_operator,
+            None => self.df_repeatability,
+        }
+    }
+
+    fn f_part(&self) -> f64 {
+        self.ms_part / self.ms_error()
+    }
+
+    fn f_operator(&self) -> Option<f64> {
+        self.ms_operator.map(|mso| mso / self.ms_error())
+    }
+
+    fn f_part_operator(&self) -> Option<f64> {
+        self.ms_part_operator
+            .map(|mspo| mspo / self.ms_repeatability)
+    }
+
+    fn p_part(&self) -> f64 {
+        stats::f_sf(self.f_part, self.df_part, self.df_error())
+    }
+
+    fn p_operator(&self) -> Option<f64> {
+        self.f_operator
+            .map(|fo| stats::f_sf(fo, self.df_operator, self.df_error()))
+    }
+
+    fn p_part_operator(&self) -> Option<f64> {
+        self.f_part_operator
+            .map(|fpo| stats::f_sf(fpo, self.df_part_operator, self.df_repeatability))
+    }
+
+    /// Whether the interaction term should be dropped from the model, which is
+    /// the case when pooling is enabled and the interaction is not significant
+    /// at `alpha_to_remove`.
+    pub fn interaction_pooled(&self) -> bool {
+        match self.p_part_operator {
+            Some(ppo) => self.pool_interaction && ppo > self.alpha_to_remove,
+            None => false,
+        }
+    }
+
+    /// Whether the interaction was tested for pooling. REML always keeps the
+    /// interaction in the model.
+    pub fn pooling_tested(&self) -> bool {
+        self.pool_interaction && self.varcomp_method_used == VarCompMethod::Anova
+    }
+
+    fn reduced_anova(&self) -> Option<ReducedAnova> {
+        if !self.interaction_pooled() {
+            return None;
+        }
+        let df_repeatability = self.df_part_operator + self.df_repeatability;
+        let ss_repeatability = self.ss_part_operator + self.ss_repeatability;
+        let ms_repeatability = ss_repeatability / df_repeatability as f64;
+        let f_part = self.ms_part / ms_repeatability;
+        let f_operator = self.ms_operator.map(|mso| mso / ms_repeatability);
+        Some(ReducedAnova {
+            df_part: self.df_part,
+            df_operator: self.df_operator,
+            df_repeatability,
+            df_total: self.df_total,
+            ss_part: self.ss_part,
+            ss_operator: self.ss_operator,
+            ss_repeatability,
+            ss_total: self.ss_total,
+            ms_part: self.ms_part,
+            ms_operator: self.ms_operator,
+            ms_repeatability,
+            f_part,
+            f_operator,
+            p_part: stats::f_sf(f_part, self.df_part, df_repeatability),
+            p_operator: f_operator.map(|fo| stats::f_sf(fo, self.df_operator, df_repeatability)),
+        })
+    }
+
+    // Repeatability mean square of the model the variance components are
+    // estimated from.
+    fn varcomp_ms_repeatability(&self) -> f64 {
+        match &self.reduced_anova {
+            Some(reduced) => reduced.ms_repeatability,
+            None => self.ms_repeatability,
+        }
+    }
+
+    // Mean square the part and operator components are measured against.
+    fn varcomp_ms_error(&self) -> f64 {
+        match &self.reduced_anova {
+            Some(reduced) => reduced.ms_repeatability,
+            None => self.ms_error(),
+        }
+    }
+
+    fn varcomp_part(&self) -> f64 {
+        (self.ms_part - self.varcomp_ms_error()) / (self.n_operator * self.n_trial) as f64
+    }
+
+    fn varcomp_operator(&self) -> Option<f64> {
+        self.ms_operator
+            .map(|mso| (mso - self.varcomp_ms_error()) / (self.n_part * self.n_trial) as f64)
+    }
+
+    fn varcomp_part_operator(&self) -> Option<f64> {
+        if self.reduced_anova.is_some() {
+            return None;
+        }
+        self.ms_part_operator
+            .map(|mspo| (mspo - self.ms_repeatability) / self.n_trial as f64)
+    }
+
+    /// Replaces the ANOVA variance components with REML estimates. The
+    /// interaction is always part of the model, REML takes it to zero when
+    /// it is not there. If REML does not converge the ANOVA estimates of a
+    /// balanced design are kept; an unbalanced design has no others.
+    fn reml_mut(&mut self) -> Result<(), GageError> {
+        // The ANOVA estimates are a good place to start from
+        let mut start = vec![self.varcomp_part];
+        if self.n_operator > 1 {
+            start.push(self.varcomp_operator.unwrap_or_default());
+            start.push(self.varcomp_part_operator.unwrap_or_default());
+        }
+        start.push(self.ms_repeatability);
+        match reml(&self.aggregates, self.ss_repeatability, &start) {
+            Some(estimates) if estimates.converged => {
+                self.varcomp_part = estimates.varcomps[0];
+                self.varcomp_operator = estimates.varcomps.get(1).copied();
+                self.varcomp_part_operator = estimates.varcomps.get(2).copied();
+                self.varcomp_repeatability = estimates.error;
+                self.varcomp_method_used = VarCompMethod::Reml;
+                // The components come from the full model, so the reduced
+                // one would only contradict them
+                self.reduced_anova = None;
+                Ok(())
+            }
+            _ if self.balanced => Ok(()),
+            _ => Err(GageError::NotConverged(
+                "The REML estimation of the variance components".to_owned(),
+            )),
+        }
+    }
+
+    /// Sets negative variance component estimates to zero, keeping track of
+    /// which ones were truncated.
+    fn truncate_varcomps_mut(&mut self) {
+        let mut truncated = Vec::new();
+        self.varcomp_part = truncate_varcomp("Part-To-Part", self.varcomp_part, &mut truncated);
+        self.varcomp_operator = self
+            .varcomp_operator
+            .map(|vo| truncate_varcomp("Operator", vo, &mut truncated));
+        self.varcomp_part_operator = self
+            .varcomp_part_operator
+            .map(|vpo| truncate_varcomp("Parts * Operator", vpo, &mut truncated));
+        self.varcomp_repeatability =
+            truncate_varcomp("Repeatability", self.varcomp_repeatability, &mut truncated);
+        self.truncated_varcomps = truncated;
+    }
+
+    fn varcomp_reproducibility(&self) -> Option<f64> {
+        match (self.varcomp_operator, self.varcomp_part_operator) {
+            (Some(vo), Some(vpo)) => Some(vo + vpo),
+            (Some(vo), None) => Some(vo),
+            _ => None,
+        }
+    }
+
+    fn varcomp_total_gagerr(&self) -> f64 {
+        match self.varcomp_reproducibility {
+            Some(vr) => self.varcomp_repeatability + vr,
+            None => self.varcomp_repeatability,
+        }
+    }
+
+    fn varcomp_total(&self) -> f64 {
+        self.varcomp_total_gagerr + self.varcomp_part
+    }
+
+    /// Confidence intervals for the variance components, from the same mean
+    /// square combinations as the estimates above.
+    fn varcomp_intervals(&self) -> Option<VarCompIntervals> {
+        let (error, repeatability) = match &self.reduced_anova {
+            Some(reduced) => {
+                let pooled = MeanSquareTerm::new(
+                    "pooled",
+                    1.0,
+                    reduced.ms_repeatability,
+                    reduced.df_repeatability,
+                );
+                (pooled, pooled)
+            }
+            None => {
+                let repeatability = MeanSquareTerm::new(
+                    "repeatability",
+                    1.0,
+                    self.ms_repeatability,
+                    self.df_repeatability,
+                );
+                let error = match self.ms_part_operator {
+                    Some(mspo) => {
+                        MeanSquareTerm::new("part_operator", 1.0, mspo, self.df_part_operator)
+                    }
+                    None => repeatability,
+                };
+                (error, repeatability)
+            }
+        };
+        if self.df_part == 0 || error.df == 0.0 || repeatability.df == 0.0 {
+            return None;
+        }
+        let difference = |source, ms, df, divisor: u32| {
+            let divisor = divisor as f64;
+            vec![
+                MeanSquareTerm::new(source, 1.0 / divisor, ms, df),
+                MeanSquareTerm {
+                    coef: -1.0 / divisor,
+                    ..error
+                },
+            ]
+        };
+        let part = difference(
+            "part",
+            self.ms_part,
+            self.df_part,
+            self.n_operator * self.n_trial,
+        );
+        let operator = self.ms_operator.map(|mso| {
+            difference(
+                "operator",
+                mso,
+                self.df_operator,
+                self.n_part * self.n_trial,
+            )
+        });
+        let part_operator = match (&self.reduced_anova, self.ms_part_operator) {
+            (None, Some(mspo)) => Some(vec![
+                MeanSquareTerm::new(
+                    "part_operator",
+                    1.0 / self.n_trial as f64,
+                    mspo,
+                    self.df_part_operator,
+                ),
+                MeanSquareTerm {
+                    coef: -1.0 / self.n_trial as f64,
+                    ..repeatability
+                },
+            ]),
+            _ => None,
+        };
+        Some(VarCompIntervals::new(
+            self.confidence,
+            &part,
+            operator.as_deref(),
+            part_operator.as_deref(),
+            repeatability,
+        ))
+    }
+
+    /// Average and Range method estimates. `None` when there are more trials
+    /// or parts than the AIAG constants cover.
+    pub fn range_method(&self) -> Option<RangeMethod> {
+        let k1 = constants::k1(self.n_trial)?;
+        let k3 = constants::k3(self.n_part)?;
+        let k2 = constants::k2(self.n_operator);
+
+        let cells = &self.aggregates.cell;
+        let r_bar = cells.iter().map(|c| c.range()).sum::<f64>() / cells.len() as f64;
+        let x_diff = range(self.aggregates.operator.iter().map(|o| o.mean()));
+        let r_part = range(self.aggregates.part.iter().map(|p| p.mean()));
+
+        let ev = r_bar * k1;
+        let av = match k2 {
+            Some(k2) => {
+                let av2 = (x_diff * k2).powi(2) - ev.powi(2) / (self.n_part * self.n_trial) as f64;
+                av2.max(0.0).sqrt()
+            }
+            None => 0.0,
+        };
+        let grr = (ev.powi(2) + av.powi(2)).sqrt();
+        let pv = r_part * k3;
+        let tv = (grr.powi(2) + pv.powi(2)).sqrt();
+        Some(RangeMethod {
+            r_bar,
+            x_diff,
+            r_part,
+            k1,
+            k2,
+            k3,
+            ev,
+            av,
+            grr,
+            pv,
+            tv,
+            ndc: ndc(pv, grr),
+        })
+    }
+}
+
+fn range<I: Iterator<Item = f64>>(values: I) -> f64 {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    max - min
+}
+
+/// A variance component estimate truncated at zero. A negative estimate
+/// means the source is too small to be told apart from the error term, so
+/// it is taken to contribute nothing and its name is added to `truncated`.
+pub fn truncate_varcomp(source: &str, varcomp: f64, truncated: &mut Vec<String>) -> f64 {
+    if varcomp < 0.0 {
+        truncated.push(source.to_owned());
+        0.0
+    } else {
+        varcomp
+    }
+}
+
+/// Number of distinct categories the gage can resolve, 1.41 times the ratio
+/// of part variation to gage variation, truncated.
+pub fn ndc(stddev_part: f64, stddev_gagerr: f64) -> u32 {
+    (1.41 * stddev_part / stddev_gagerr).floor().max(0.0) as u32
+}
+
+/// Part-to-part standard deviation left of a historical process standard
+/// deviation once the gage variation is taken out.
+pub fn process_stddev_part(historical_stddev: f64, stddev_gagerr: f64) -> f64 {
+    (historical_stddev.powi(2) - stddev_gagerr.powi(2))
+        .max(0.0)
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::confidence_interval::Interval;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    /// AIAG MSA example: ten parts measured three times by each of three
+    /// appraisers. Rows are trials, columns parts 1 to 10.
+    const AIAG: [[[f64; 10]; 3]; 3] = [
+        [
+            [
+                0.29, -0.56, 1.34, 0.47, -0.80, 0.02, 0.59, -0.31, 2.26, -1.36,
+            ],
+            [
+                0.41, -0.68, 1.17, 0.50, -0.92, -0.11, 0.75, -0.20, 1.99, -1.25,
+            ],
+            [
+                0.64, -0.58, 1.27, 0.64, -0.84, -0.21, 0.66, -0.17, 2.01, -1.31,
+            ],
+        ],
+        [
+            [
+                0.08, -0.47, 1.19, 0.01, -0.56, -0.20, 0.47, -0.63, 1.80, -1.68,
+            ],
+            [
+                0.25, -1.22, 0.94, 1.03, -1.20, 0.22, 0.55, 0.08, 2.12, -1.62,
+            ],
+            [
+                0.07, -0.68, 1.34, 0.20, -1.28, 0.06, 0.83, -0.34, 2.19, -1.50,
+            ],
+        ],
+        [
+            [
+                0.04, -1.38, 0.88, 0.14, -1.46, -0.29, 0.02, -0.46, 1.77, -1.49,
+            ],
+            [
+                -0.11, -1.13, 1.09, 0.20, -1.07, -0.67, 0.01, -0.56, 1.45, -1.77,
+            ],
+            [
+                -0.15, -0.96, 0.67, 0.11, -1.45, -0.49, 0.21, -0.49, 1.87, -2.16,
+            ],
+        ],
+    ];
+
+    fn aiag_data() -> Vec<GageStudyData> {
+        let mut data = Vec::new();
+        for (operator, trials) in AIAG.iter().enumerate() {
+            for (trial, values) in trials.iter().enumerate() {
+                for (part, value) in values.iter().enumerate() {
+                    data.push(GageStudyData::new(
+                        &(part + 1).to_string(),
+                        operator as u32 + 1,
+                        trial as u32 + 1,
+                        *value,
+                    ));
+                }
+            }
+        }
+        data
+    }
+
+    fn aiag_study() -> GageStudyDataSet {
+        GageStudyDataSet::new_with_data(aiag_data())
+            .calculate()
+            .unwrap()
+    }
+
+    /// Balanced design with a clear part/operator interaction, so that no
+    /// variance component estimate is negative.
+    fn interaction_data() -> Vec<GageStudyData> {
+        let mut data = Vec::new();
+        for part in 1..=6u32 {
+            for operator in 1..=3u32 {
+                let cell = part as f64
+                    + 0.3 * operator as f64
+                    + 0.1 * ((part * 7 + operator * 3) % 5) as f64;
+                for trial in 1..=3u32 {
+                    let noise = 0.02 * ((part + 2 * operator + 3 * trial) % 4) as f64;
+                    data.push(GageStudyData::new(
+                        &part.to_string(),
+                        operator,
+                        trial,
+                        cell + noise,
+                    ));
+                }
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn anova_matches_aiag_example() {
+        let study = aiag_study();
+        assert!(study.balanced);
+        assert_eq!(
+            (study.df_part, study.df_operator, study.df_part_operator),
+            (9, 2, 18)
+        );
+        assert_eq!((study.df_repeatability, study.df_total), (60, 89));
+        assert_close(study.ss_part, 88.3619, 1e-4);
+        assert_close(study.ss_operator, 3.1673, 1e-4);
+        assert_close(study.ss_part_operator, 0.3590, 1e-4);
+        assert_close(study.ss_repeatability, 2.7589, 1e-4);
+        assert_close(study.ss_total, 94.6471, 1e-4);
+        assert_close(study.f_part, 492.29, 1e-2);
+        assert_close(study.f_operator.unwrap(), 79.41, 1e-2);
+        assert_close(study.f_part_operator.unwrap(), 0.434, 1e-3);
+    }
+
+    #[test]
+    fn p_values_match_aiag_example() {
+        let study = aiag_study();
+        assert!(study.p_part < 1e-10);
+        assert!(study.p_operator.unwrap() < 1e-6);
+        assert_close(study.p_part_operator.unwrap(), 0.974, 1e-3);
+    }
+
+    #[test]
+    fn interaction_is_pooled_as_in_aiag_example() {
+        let study = aiag_study();
+        let reduced = study.reduced_anova.as_ref().unwrap();
+        assert_eq!(reduced.df_repeatability, 78);
+        assert_close(reduced.f_part, 245.614, 1e-3);
+        assert_close(reduced.f_operator.unwrap(), 39.617, 1e-3);
+        assert_close(reduced.ms_repeatability, 0.03997, 1e-5);
+
+        assert_eq!(study.varcomp_part_operator, None);
+        assert_close(study.varcomp_repeatability, 0.03997, 1e-5);
+        assert_close(study.varcomp_operator.unwrap(), 0.05146, 1e-5);
+        assert_close(study.varcomp_reproducibility.unwrap(), 0.05146, 1e-5);
+        assert_close(study.varcomp_total_gagerr, 0.09143, 1e-5);
+        assert_close(study.varcomp_part, 1.08645, 1e-5);
+        assert_close(study.varcomp_total, 1.17788, 1e-5);
+        assert_close(study.stddev_total_gagerr, 0.30237, 1e-5);
+        assert_close(study.stddev_total, 1.08530, 1e-5);
+    }
+
+    #[test]
+    fn interaction_is_kept_when_pooling_is_off() {
+        let mut study = GageStudyDataSet::new_with_data(aiag_data());
+        study.pool_interaction = false;
+        let study = study.calculate().unwrap();
+        assert!(study.reduced_anova.is_none());
+        assert_close(study.varcomp_repeatability, 2.7589 / 60.0, 1e-5);
+        assert_eq!(study.varcomp_part_operator, Some(0.0));
+        assert_eq!(
+            study.truncated_varcomps,
+            vec!["Parts * Operator".to_owned()]
+        );
+    }
+
+    // The published figures are computed from rounded intermediate values,
+    // hence the looser tolerance.
+    #[test]
+    fn range_method_matches_aiag_example() {
+        let range = aiag_study().range_method.unwrap();
+        assert_close(range.k1, 0.5908, 1e-4);
+        assert_close(range.k2.unwrap(), 0.5231, 1e-4);
+        assert_close(range.k3, 0.3146, 1e-4);
+        assert_close(range.r_bar, 0.3417, 1e-4);
+        assert_close(range.x_diff, 0.4446, 1e-4);
+        assert_close(range.r_part, 3.511, 1e-3);
+        assert_close(range.ev, 0.20188, 5e-4);
+        assert_close(range.av, 0.22963, 5e-4);
+        assert_close(range.grr, 0.30575, 5e-4);
+        assert_close(range.pv, 1.10456, 5e-4);
+        assert_close(range.tv, 1.14610, 5e-4);
+        assert_eq!(range.ndc, 5);
+    }
+
+    #[test]
+    fn ndc_matches_aiag_example() {
+        // 1.41 × 1.04233 / 0.30237 = 4.86
+        assert_eq!(aiag_study().ndc, 4);
+        assert_eq!(ndc(0.0, 0.5), 0);
+    }
+
+    #[test]
+    fn repeatability_interval_matches_chi_square_table() {
+        // 78 MS / χ²(0.975, 78) and 78 MS / χ²(0.025, 78)
+        let interval = aiag_study().varcomp_intervals.unwrap().repeatability;
+        assert_close(interval.lower, 0.029889, 1e-5);
+        assert_close(interval.upper, 0.056213, 1e-5);
+    }
+
+    #[test]
+    fn intervals_contain_the_estimates() {
+        let study = aiag_study();
+        let intervals = study.varcomp_intervals.as_ref().unwrap();
+        let contains = |interval: Interval, estimate: f64| {
+            interval.lower <= estimate && estimate <= interval.upper
+        };
+        assert!(contains(intervals.part, study.varcomp_part));
+        assert!(contains(
+            intervals.operator.unwrap(),
+            study.varcomp_operator.unwrap()
+        ));
+        assert!(intervals.part_operator.is_none());
+        assert!(contains(intervals.total_gagerr, study.varcomp_total_gagerr));
+        assert!(contains(intervals.total, study.varcomp_total));
+        assert!(contains(
+            intervals.contribution.total_gagerr,
+            study.varcomp_total_gagerr / study.varcomp_total
+        ));
+        assert!(intervals.ndc.lower <= 4.0 && 4.0 <= intervals.ndc.upper);
+    }
+
+    #[test]
+    fn trials_are_counted_per_cell_whatever_the_labels() {
+        // Every operator numbers their trials on from the previous one
+        let mut data = aiag_data();
+        for d in data.iter_mut() {
+            d.trial += 3 * (d.operator - 1);
+        }
+        let study = GageStudyDataSet::new_with_data(data).calculate().unwrap();
+        assert_eq!(study.trials.len(), 9);
+        assert_eq!(study.n_trial, 3);
+        let reference = aiag_study();
+        assert_close(study.varcomp_part, reference.varcomp_part, 1e-12);
+        assert_close(
+            study.varcomp_operator.unwrap(),
+            reference.varcomp_operator.unwrap(),
+            1e-12,
+        );
+        assert_close(
+            study.varcomp_total_gagerr,
+            reference.varcomp_total_gagerr,
+            1e-12,
+        );
+        assert_eq!(study.ndc, reference.ndc);
+        let (range, reference_range) =
+            (study.range_method.unwrap(), reference.range_method.unwrap());
+        assert_close(range.k1, reference_range.k1, 1e-12);
+        assert_close(range.ev, reference_range.ev, 1e-12);
+        assert_close(range.av, reference_range.av, 1e-12);
+        assert_eq!(range.ndc, reference_range.ndc);
+    }
+
+    /// The AIAG example without its last measurement.
+    fn unbalanced_data() -> Vec<GageStudyData> {
+        let mut data = aiag_data();
+        data.pop();
+        data
+    }
+
+    // Reference sums of squares from least squares fits of the nested
+    // models, e.g. R(Operator | Part) = RSS(Part) - RSS(Part + Operator)
+    #[test]
+    fn unbalanced_design_uses_adjusted_sums_of_squares() {
+        let study = GageStudyDataSet::new_with_data(unbalanced_data())
+            .calculate()
+            .unwrap();
+        assert!(!study.balanced);
+        assert_eq!(
+            (
+                study.df_part,
+                study.df_operator,
+                study.df_part_operator,
+                study.df_repeatability
+            ),
+            (9, 2, 18, 59)
+        );
+        assert_close(study.ss_part, 84.733177, 1e-6);
+        assert_close(study.ss_operator, 2.905158, 1e-6);
+        assert_close(study.ss_part_operator, 0.418215, 1e-6);
+        assert_close(study.ss_repeatability, 2.571667, 1e-6);
+    }
+
+    // Reference values from a direct maximization of the restricted
+    // likelihood over the full covariance matrix of the observations
+    #[test]
+    fn unbalanced_design_is_estimated_by_reml() {
+        let study = GageStudyDataSet::new_with_data(unbalanced_data())
+            .calculate()
+            .unwrap();
+        assert_eq!(study.varcomp_method_used, VarCompMethod::Reml);
+        assert_close(study.varcomp_part, 1.071336, 1e-5);
+        assert_close(study.varcomp_operator.unwrap(), 0.048168, 1e-5);
+        assert_close(study.varcomp_part_operator.unwrap(), 0.0, 1e-6);
+        assert_close(study.varcomp_repeatability, 0.038831, 1e-5);
+        // The results of the pooled and balanced analyses do not apply
+        assert!(study.reduced_anova.is_none());
+        assert!(!study.pooling_tested());
+        assert!(study.range_method.is_none());
+        assert!(study.varcomp_intervals.is_none());
+    }
+
+    #[test]
+    fn reml_matches_anova_when_balanced() {
+        let mut anova = GageStudyDataSet::new_with_data(interaction_data());
+        anova.pool_interaction = false;
+        let anova = anova.calculate().unwrap();
+        assert!(anova.truncated_varcomps.is_empty());
+
+        let mut reml = GageStudyDataSet::new_with_data(interaction_data());
+        reml.varcomp_method = VarCompMethod::Reml;
+        let reml = reml.calculate().unwrap();
+        assert_eq!(reml.varcomp_method_used, VarCompMethod::Reml);
+
+        assert_close(reml.varcomp_part, anova.varcomp_part, 1e-6);
+        assert_close(
+            reml.varcomp_operator.unwrap(),
+            anova.varcomp_operator.unwrap(),
+            1e-6,
+        );
+        assert_close(
+            reml.varcomp_part_operator.unwrap(),
+            anova.varcomp_part_operator.unwrap(),
+            1e-6,
+        );
+        assert_close(
+            reml.varcomp_repeatability,
+            anova.varcomp_repeatability,
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn disconnected_design_is_rejected() {
+        // Parts 1 to 5 measured only by operator 1, the others only by 2
+        let data = aiag_data()
+            .into_iter()
+            .filter(|d| d.operator < 3)
+            .filter(|d| (d.part.parse::<u32>().unwrap() <= 5) == (d.operator == 1))
+            .collect();
+        let error = GageStudyDataSet::new_with_data(data).calculate().err();
+        assert!(matches!(error, Some(GageError::UnbalancedDesign(_))));
+    }
+
+    #[test]
+    fn incomplete_design_is_estimated_by_reml() {
+        // Operator 3 never measured part 10
+        let data = aiag_data()
+            .into_iter()
+            .filter(|d| !(d.operator == 3 && d.part == "10"))
+            .collect();
+        let study = GageStudyDataSet::new_with_data(data).calculate().unwrap();
+        assert!(!study.balanced);
+        assert_eq!(study.df_part_operator, 17);
+        assert_eq!(study.varcomp_method_used, VarCompMethod::Reml);
+        assert!(study.varcomp_total.is_finite());
+    }
+
+    #[test]
+    fn invalid_data_is_reported() {
+        let calculate =
+            |data: Vec<GageStudyData>| GageStudyDataSet::new_with_data(data).calculate().err();
+        assert_eq!(calculate(Vec::new()), Some(GageError::EmptyData));
+
+        let mut data = aiag_data();
+        data[4].value = f64::NAN;
+        assert!(matches!(calculate(data), Some(GageError::NonFinite(_))));
+
+        let data = aiag_data().into_iter().filter(|d| d.trial == 1).collect();
+        assert_eq!(
+            calculate(data),
+            Some(GageError::TooFewLevels {
+                factor: "Trial".to_owned(),
+                found: 1,
+                required: 2,
+            })
+        );
+
+        let mut data = aiag_data();
+        for d in data.iter_mut() {
+            d.value = d.part.parse().unwrap();
+        }
+        assert_eq!(calculate(data), Some(GageError::ZeroVariance));
+    }
+}
@@ -0,0 +1,23 @@
+//! Statistics of measurement system analysis: crossed, nested and expanded
+//! Gage R&R, Type 1 gage studies, linearity and attribute agreement.
+//!
+//! The crate has no dependencies. Enable the `serde` feature to serialize
+//! the results.
+
+pub mod aggregates;
+pub mod attribute_agreement;
+pub mod confidence_interval;
+pub mod constants;
+pub mod expanded_gage_study_data_set;
+pub mod gage_error;
+pub mod gage_study_data;
+pub mod gage_study_data_set;
+pub mod linearity_study;
+pub mod measurement_plots;
+pub mod nested_gage_study_data_set;
+pub mod reml;
+pub mod spec_limits;
+pub mod stats;
+pub mod study_type;
+pub mod type1_gage_study;
+pub mod xbar_r_chart;
@@ -0,0 +1,272 @@
+use crate::gage_study_data::GageStudyData;
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Bias of the measurements at one reference value, or of all measurements
+/// for the average row.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ReferenceBias {
+    pub reference: Option<f64>,
+    pub n: u32,
+    pub bias: f64,
+    pub stddev: f64,
+    pub t: f64,
+    pub p: f64,
+    /// Bias as a percentage of the process variation, when one is given
+    pub percent_bias: Option<f64>,
+}
+
+/// AIAG gage linearity and bias study: reference parts spread over the
+/// operating range are measured repeatedly and the bias is regressed on the
+/// reference value.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct LinearityStudy {
+    pub data: Vec<GageStudyData>,
+    /// Reference value per part, overriding the `reference` of the data
+    pub references: BTreeMap<String, f64>,
+    pub process_variation: Option<f64>,
+    pub n: u32,
+    pub slope: f64,
+    pub intercept: f64,
+    pub se_slope: f64,
+    pub se_intercept: f64,
+    pub t_slope: f64,
+    pub t_intercept: f64,
+    pub p_slope: f64,
+    pub p_intercept: f64,
+    /// Standard error of the regression
+    pub s: f64,
+    pub r_squared: f64,
+    pub linearity: Option<f64>,
+    pub percent_linearity: f64,
+    pub bias: Vec<ReferenceBias>,
+    pub average_bias: Option<ReferenceBias>,
+}
+
+impl Default for LinearityStudy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinearityStudy {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::<GageStudyData>::new(),
+            references: BTreeMap::new(),
+            process_variation: None,
+            n: 0,
+            slope: 0.0,
+            intercept: 0.0,
+            se_slope: 0.0,
+            se_intercept: 0.0,
+            t_slope: 0.0,
+            t_intercept: 0.0,
+            p_slope: 0.0,
+            p_intercept: 0.0,
+            s: 0.0,
+            r_squared: 0.0,
+            linearity: None,
+            percent_linearity: 0.0,
+            bias: Vec::new(),
+            average_bias: None,
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>, references: BTreeMap<String, f64>) -> Self {
+        let mut study = Self::new();
+        study.data = data;
+        study.references = references;
+        study
+    }
+
+    /// Reference value of a measurement, from the per-part mapping or the
+    /// measurement itself.
+    pub fn reference(&self, data: &GageStudyData) -> Option<f64> {
+        self.references.get(&data.part).copied().or(data.reference)
+    }
+
+    /// `(reference, bias)` of every measurement that has a reference value.
+    pub fn biases(&self) -> Vec<(f64, f64)> {
+        self.data
+            .iter()
+            .filter_map(|d| self.reference(d).map(|r| (r, d.value - r)))
+            .collect()
+    }
+
+    pub fn calculate_mut(&mut self) -> &mut Self {
+        let points = self.biases();
+        self.n = points.len() as u32;
+        let n = self.n as f64;
+
+        // Least squares fit of bias against reference
+        let x_mean = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let y_mean = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let sxx: f64 = points.iter().map(|(x, _)| (x - x_mean).powi(2)).sum();
+        let sxy: f64 = points
+            .iter()
+            .map(|(x, y)| (x - x_mean) * (y - y_mean))
+            .sum();
+        let syy: f64 = points.iter().map(|(_, y)| (y - y_mean).powi(2)).sum();
+        self.slope = sxy / sxx;
+        self.intercept = y_mean - self.slope * x_mean;
+        let sse: f64 = points
+            .iter()
+            .map(|(x, y)| (y - self.intercept - self.slope * x).powi(2))
+            .sum();
+        self.s = (sse / (n - 2.0)).sqrt();
+        self.r_squared = 1.0 - sse / syy;
+        self.se_slope = self.s / sxx.sqrt();
+        self.se_intercept = self.s * (1.0 / n + x_mean.powi(2) / sxx).sqrt();
+        self.t_slope = self.slope / self.se_slope;
+        self.t_intercept = self.intercept / self.se_intercept;
+        self.p_slope = stats::t_sf_two_sided(self.t_slope, n - 2.0);
+        self.p_intercept = stats::t_sf_two_sided(self.t_intercept, n - 2.0);
+        self.percent_linearity = self.slope.abs() * 100.0;
+        self.linearity = self.process_variation.map(|pv| self.slope.abs() * pv);
+
+        // Bias at each reference value
+        let mut sorted = points;
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut groups: Vec<(f64, Vec<f64>)> = Vec::new();
+        for (x, y) in sorted {
+            match groups.last_mut() {
+                Some((reference, biases)) if *reference == x => biases.push(y),
+                _ => groups.push((x, vec![y])),
+            }
+        }
+        self.bias = groups
+            .iter()
+            .map(|(reference, biases)| self.reference_bias(Some(*reference), biases))
+            .collect();
+
+        // Average bias, tested against the pooled within-reference deviation
+        let df: f64 = self.bias.iter().map(|b| b.n as f64 - 1.0).sum();
+        let pooled = (self
+            .bias
+            .iter()
+            .map(|b| (b.n as f64 - 1.0) * b.stddev.powi(2))
+            .sum::<f64>()
+            / df)
+            .sqrt();
+        let t = y_mean.abs() / (pooled / n.sqrt());
+        self.average_bias = Some(ReferenceBias {
+            reference: None,
+            n: self.n,
+            bias: y_mean,
+            stddev: pooled,
+            t,
+            p: stats::t_sf_two_sided(t, df),
+            percent_bias: self.percent_bias(y_mean),
+        });
+        self
+    }
+
+    pub fn calculate(mut self) -> Self {
+        self.calculate_mut();
+        self
+    }
+
+    fn reference_bias(&self, reference: Option<f64>, biases: &[f64]) -> ReferenceBias {
+        let n = biases.len() as f64;
+        let bias = biases.iter().sum::<f64>() / n;
+        let stddev = (biases.iter().map(|b| (b - bias).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+        let t = bias.abs() / (stddev / n.sqrt());
+        ReferenceBias {
+            reference,
+            n: biases.len() as u32,
+            bias,
+            stddev,
+            t,
+            p: stats::t_sf_two_sided(t, n - 1.0),
+            percent_bias: self.percent_bias(bias),
+        }
+    }
+
+    fn percent_bias(&self, bias: f64) -> Option<f64> {
+        self.process_variation.map(|pv| bias.abs() / pv * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    /// AIAG MSA linearity example: five parts with reference values 2 to 10,
+    /// each measured twelve times.
+    fn aiag_study() -> LinearityStudy {
+        let measurements = [
+            (
+                2.0,
+                [2.7, 2.5, 2.4, 2.5, 2.7, 2.3, 2.5, 2.5, 2.4, 2.4, 2.6, 2.4],
+            ),
+            (
+                4.0,
+                [5.1, 3.9, 4.2, 5.0, 3.8, 3.9, 3.9, 3.9, 3.9, 4.0, 4.1, 3.8],
+            ),
+            (
+                6.0,
+                [5.8, 5.7, 5.9, 5.9, 6.0, 6.1, 6.0, 6.1, 6.4, 6.3, 6.0, 6.1],
+            ),
+            (
+                8.0,
+                [7.6, 7.7, 7.8, 7.7, 7.8, 7.8, 7.8, 7.7, 7.8, 7.5, 7.6, 7.7],
+            ),
+            (
+                10.0,
+                [9.1, 9.3, 9.5, 9.3, 9.4, 9.5, 9.5, 9.5, 9.6, 9.2, 9.3, 9.4],
+            ),
+        ];
+        let mut data = Vec::new();
+        let mut references = BTreeMap::new();
+        for (part, (reference, values)) in measurements.iter().enumerate() {
+            let part = (part + 1).to_string();
+            references.insert(part.clone(), *reference);
+            for (trial, value) in values.iter().enumerate() {
+                data.push(GageStudyData::new(&part, 1, trial as u32 + 1, *value));
+            }
+        }
+        let mut study = LinearityStudy::new_with_data(data, references);
+        study.process_variation = Some(6.0);
+        study.calculate()
+    }
+
+    #[test]
+    fn regression_matches_aiag_example() {
+        let study = aiag_study();
+        assert_eq!(study.n, 60);
+        assert_close(study.slope, -0.131667, 1e-6);
+        assert_close(study.intercept, 0.736667, 1e-6);
+        assert_close(study.se_slope, 0.010933, 1e-6);
+        assert_close(study.se_intercept, 0.072524, 1e-6);
+        assert_close(study.s, 0.239540, 1e-6);
+        assert_close(study.r_squared, 0.714318, 1e-6);
+        assert!(study.p_slope < 1e-10);
+        assert_close(study.linearity.unwrap(), 0.79, 1e-12);
+        assert_close(study.percent_linearity, 13.1667, 1e-4);
+    }
+
+    #[test]
+    fn bias_matches_aiag_example() {
+        let study = aiag_study();
+        let bias: Vec<f64> = study.bias.iter().map(|b| b.bias).collect();
+        let expected = [0.491667, 0.125, 0.025, -0.291667, -0.616667];
+        assert_eq!(bias.len(), expected.len());
+        for (actual, expected) in bias.iter().zip(expected) {
+            assert_close(*actual, expected, 1e-6);
+        }
+        let average = study.average_bias.unwrap();
+        assert_close(average.bias, -0.053333, 1e-6);
+        assert_close(average.percent_bias.unwrap(), 0.888889, 1e-6);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::aggregates::Aggregates;
+use crate::gage_error::{check_measurements, GageError};
+use crate::gage_study_data::GageStudyData;
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Measurements of one part by all operators.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct PartValues {
+    pub part: String,
+    pub values: Vec<f64>,
+    pub mean: f64,
+}
+
+/// Five number summary of the measurements of one operator. Whiskers reach
+/// the most extreme values within 1.5 interquartile ranges of the box, the
+/// values beyond them are outliers.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Boxplot {
+    pub operator: u32,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub lower_whisker: f64,
+    pub upper_whisker: f64,
+    pub outliers: Vec<f64>,
+    pub mean: f64,
+}
+
+impl Boxplot {
+    /// Summary of values that are already sorted.
+    pub fn new(operator: u32, sorted: &[f64]) -> Self {
+        let q1 = stats::sample_quantile(sorted, 0.25);
+        let q3 = stats::sample_quantile(sorted, 0.75);
+        let iqr = q3 - q1;
+        let fence = q1 - 1.5 * iqr..=q3 + 1.5 * iqr;
+        let inside = || sorted.iter().copied().filter(|v| fence.contains(v));
+        Self {
+            operator,
+            q1,
+            median: stats::sample_quantile(sorted, 0.5),
+            q3,
+            lower_whisker: inside().fold(f64::INFINITY, f64::min),
+            upper_whisker: inside().fold(f64::NEG_INFINITY, f64::max),
+            outliers: sorted
+                .iter()
+                .copied()
+                .filter(|v| !fence.contains(v))
+                .collect(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        }
+    }
+}
+
+/// Average of every part by one operator, `None` for the parts the operator
+/// did not measure.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct InteractionLine {
+    pub operator: u32,
+    pub means: Vec<Option<f64>>,
+}
+
+/// Measurement by part, measurement by operator and part * operator
+/// interaction plots of a crossed study. Parts are in order of appearance
+/// and operators in ascending order.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MeasurementPlotData {
+    pub data: Vec<GageStudyData>,
+    pub by_part: Vec<PartValues>,
+    pub by_operator: Vec<Boxplot>,
+    /// Lines over the parts of `by_part`
+    pub interaction: Vec<InteractionLine>,
+}
+
+impl Default for MeasurementPlotData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MeasurementPlotData {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            by_part: Vec::new(),
+            by_operator: Vec::new(),
+            interaction: Vec::new(),
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>) -> Self {
+        let mut plots = Self::new();
+        plots.data = data;
+        plots
+    }
+
+    pub fn calculate_mut(&mut self) -> Result<&mut Self, GageError> {
+        check_measurements(&self.data)?;
+        let aggregates = Aggregates::new(&self.data);
+
+        let mut part_values: Vec<Vec<f64>> = vec![Vec::new(); aggregates.parts.len()];
+        let mut operator_values: Vec<Vec<f64>> = vec![Vec::new(); aggregates.operators.len()];
+        for (d, cell) in self.data.iter().zip(aggregates.cell_of.iter()) {
+            let (part, operator) = aggregates.cells[*cell];
+            part_values[part].push(d.value);
+            operator_values[operator].push(d.value);
+        }
+        self.by_part = part_values
+            .into_iter()
+            .enumerate()
+            .map(|(part, values)| PartValues {
+                part: aggregates.parts[part].clone(),
+                values,
+                mean: aggregates.part[part].mean(),
+            })
+            .collect();
+
+        let mut operators: Vec<usize> = (0..aggregates.operators.len()).collect();
+        operators.sort_by_key(|o| aggregates.operators[*o]);
+        self.by_operator = operators
+            .iter()
+            .map(|o| {
+                let values = &mut operator_values[*o];
+                values.sort_by(f64::total_cmp);
+                Boxplot::new(aggregates.operators[*o], values)
+            })
+            .collect();
+
+        let mut means = vec![vec![None; aggregates.parts.len()]; aggregates.operators.len()];
+        for (cell, (part, operator)) in aggregates.cells.iter().enumerate() {
+            means[*operator][*part] = Some(aggregates.cell[cell].mean());
+        }
+        self.interaction = operators
+            .iter()
+            .map(|o| InteractionLine {
+                operator: aggregates.operators[*o],
+                means: means[*o].clone(),
+            })
+            .collect();
+        Ok(self)
+    }
+
+    pub fn calculate(mut self) -> Result<Self, GageError> {
+        self.calculate_mut()?;
+        Ok(self)
+    }
+}
@@ -0,0 +1,436 @@
+use crate::aggregates::Aggregates;
+use crate::confidence_interval::{MeanSquareTerm, VarCompIntervals};
+use crate::gage_error::{check_levels, check_measurements, GageError};
+use crate::gage_study_data::GageStudyData;
+use crate::gage_study_data_set::{ndc, process_stddev_part, truncate_varcomp};
+use crate::spec_limits::SpecLimits;
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// Gage R&R for destructive testing, where each operator measures their own
+/// set of parts and the parts are therefore nested within operators.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct NestedGageStudyDataSet {
+    pub data: Vec<GageStudyData>,
+    /// Sums and counts of `data` by operator and part, the cells being the
+    /// parts since each is measured by a single operator
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub aggregates: Aggregates,
+    pub parts: HashSet<(u32, String)>,
+    pub operators: HashSet<u32>,
+    pub trials: HashSet<u32>,
+    pub study_variation: f64,
+    pub spec_limits: SpecLimits,
+    /// Tolerance from the spec limits, if any were given
+    pub tolerance: Option<f64>,
+    pub historical_stddev: Option<f64>,
+    pub confidence: f64,
+    pub mean: f64,
+    pub n_part: u32,
+    pub n_operator: u32,
+    /// Measurements of every part, whatever the trials are labelled
+    pub n_trial: u32,
+    pub n_total: u32,
+    pub df_operator: u32,
+    pub df_part: u32,
+    pub df_repeatability: u32,
+    pub df_total: u32,
+    pub ss_operator: f64,
+    pub ss_part: f64,
+    pub ss_repeatability: f64,
+    pub ss_total: f64,
+    pub ms_operator: Option<f64>,
+    pub ms_part: f64,
+    pub ms_repeatability: f64,
+    pub f_operator: Option<f64>,
+    pub f_part: f64,
+    pub p_operator: Option<f64>,
+    pub p_part: f64,
+    pub varcomp_operator: Option<f64>,
+    pub varcomp_part: f64,
+    pub varcomp_repeatability: f64,
+    pub varcomp_total_gagerr: f64,
+    pub varcomp_total: f64,
+    /// Sources whose negative estimate was set to zero
+    pub truncated_varcomps: Vec<String>,
+    pub stddev_operator: Option<f64>,
+    pub stddev_part: f64,
+    pub stddev_repeatability: f64,
+    pub stddev_total_gagerr: f64,
+    pub stddev_total: f64,
+    pub ndc: u32,
+    pub stddev_part_process: Option<f64>,
+    pub varcomp_intervals: Option<VarCompIntervals>,
+}
+
+impl Default for NestedGageStudyDataSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NestedGageStudyDataSet {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::<GageStudyData>::new(),
+            aggregates: Aggregates::default(),
+            parts: HashSet::new(),
+            operators: HashSet::new(),
+            trials: HashSet::new(),
+            study_variation: 5.15,
+            spec_limits: SpecLimits::default(),
+            tolerance: None,
+            historical_stddev: None,
+            confidence: 0.95,
+            mean: 0.0,
+            n_part: 0,
+            n_operator: 0,
+            n_trial: 0,
+            n_total: 0,
+            df_operator: 0,
+            df_part: 0,
+            df_repeatability: 0,
+            df_total: 0,
+            ss_operator: 0.0,
+            ss_part: 0.0,
+            ss_repeatability: 0.0,
+            ss_total: 0.0,
+            ms_operator: None,
+            ms_part: 0.0,
+            ms_repeatability: 0.0,
+            f_operator: None,
+            f_part: 0.0,
+            p_operator: None,
+            p_part: 0.0,
+            varcomp_operator: None,
+            varcomp_part: 0.0,
+            varcomp_repeatability: 0.0,
+            varcomp_total_gagerr: 0.0,
+            varcomp_total: 0.0,
+            truncated_varcomps: Vec::new(),
+            stddev_operator: None,
+            stddev_part: 0.0,
+            stddev_repeatability: 0.0,
+            stddev_total_gagerr: 0.0,
+            stddev_total: 0.0,
+            ndc: 0,
+            stddev_part_process: None,
+            varcomp_intervals: None,
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>) -> Self {
+        let mut gagerr = Self::new();
+        gagerr.data = data;
+        gagerr
+    }
+
+    pub fn calculate_mut(&mut self) -> Result<&mut Self, GageError> {
+        check_measurements(&self.data)?;
+        self.aggregates = Aggregates::new(&self.data);
+        self.mean = self.mean();
+        self.tolerance = self.spec_limits.tolerance(self.mean);
+        self.parts = self.unique_parts();
+        self.operators = self.unique_operators();
+        self.trials = self.unique_trials();
+        self.n_operator = self.operators.len() as u32;
+        // Parts per operator
+        self.n_part = self.parts.len() as u32 / self.n_operator;
+        self.n_total = self.data.len() as u32;
+        self.check_balanced()?;
+        // Trial labels need not be the same for every part, so the
+        // replicates are counted per part rather than taken from the labels
+        self.n_trial = self.aggregates.cell[0].count;
+        check_levels("Part per operator", self.n_part as usize, 2)?;
+        check_levels("Trial", self.n_trial as usize, 2)?;
+        if self.aggregates.cell.iter().all(|c| c.range() == 0.0) {
+            return Err(GageError::ZeroVariance);
+        }
+        self.df_operator = self.n_operator - 1;
+        self.df_part = self.parts.len() as u32 - self.n_operator;
+        self.df_total = self.n_total - 1;
+        self.df_repeatability = self.n_total - self.parts.len() as u32;
+        self.ss_operator = self.ss_operator();
+        self.ss_part = self.ss_part();
+        self.ss_repeatability = self.ss_repeatability();
+        self.ss_total = self.ss_total();
+        self.ms_operator = self.ms_operator();
+        self.ms_part = self.ss_part / self.df_part as f64;
+        self.ms_repeatability = self.ss_repeatability / self.df_repeatability as f64;
+        self.f_operator = self.ms_operator.map(|mso| mso / self.ms_part);
+        self.f_part = self.ms_part / self.ms_repeatability;
+        self.p_operator = self
+            .f_operator
+            .map(|fo| stats::f_sf(fo, self.df_operator, self.df_part));
+        self.p_part = stats::f_sf(self.f_part, self.df_part, self.df_repeatability);
+        let mut truncated = Vec::new();
+        self.varcomp_repeatability = self.ms_repeatability;
+        self.varcomp_part = truncate_varcomp(
+            "Part-To-Part",
+            (self.ms_part - self.ms_repeatability) / self.n_trial as f64,
+            &mut truncated,
+        );
+        self.varcomp_operator = self.ms_operator.map(|mso| {
+            let varcomp = (mso - self.ms_part) / (self.n_part * self.n_trial) as f64;
+            truncate_varcomp("Operator", varcomp, &mut truncated)
+        });
+        self.truncated_varcomps = truncated;
+        self.varcomp_total_gagerr =
+            self.varcomp_repeatability + self.varcomp_operator.unwrap_or(0.0);
+        self.varcomp_total = self.varcomp_total_gagerr + self.varcomp_part;
+        self.stddev_operator = self.varcomp_operator.map(f64::sqrt);
+        self.stddev_part = self.varcomp_part.sqrt();
+        self.stddev_repeatability = self.varcomp_repeatability.sqrt();
+        self.stddev_total_gagerr = self.varcomp_total_gagerr.sqrt();
+        self.stddev_total = self.varcomp_total.sqrt();
+        self.ndc = ndc(self.stddev_part, self.stddev_total_gagerr);
+        self.stddev_part_process = self
+            .historical_stddev
+            .map(|h| process_stddev_part(h, self.stddev_total_gagerr));
+        self.varcomp_intervals = self.varcomp_intervals();
+        if !self.varcomp_total.is_finite() {
+            return Err(GageError::NonFinite("the variance components".to_owned()));
+        }
+        Ok(self)
+    }
+
+    pub fn calculate(mut self) -> Result<Self, GageError> {
+        self.calculate_mut()?;
+        Ok(self)
+    }
+
+    /// The variance components assume every operator measured the same
+    /// number of parts, and every part was measured the same number of times.
+    fn check_balanced(&self) -> Result<(), GageError> {
+        let mut parts_per_operator: HashMap<u32, u32> = HashMap::new();
+        for (operator, _) in self.parts.iter() {
+            *parts_per_operator.entry(*operator).or_default() += 1;
+        }
+        if !all_equal(parts_per_operator.into_values()) {
+            return Err(GageError::UnbalancedDesign(
+                "operators measured different numbers of parts".to_owned(),
+            ));
+        }
+        if !all_equal(self.aggregates.cell.iter().map(|c| c.count)) {
+            return Err(GageError::UnbalancedDesign(
+                "parts were measured different numbers of times".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.aggregates.total.mean()
+    }
+
+    /// Parts are identified by the operator that measured them, so part labels
+    /// may be reused between operators.
+    pub fn unique_parts(&self) -> HashSet<(u32, String)> {
+        let aggregates = &self.aggregates;
+        aggregates
+            .cells
+            .iter()
+            .map(|(p, o)| (aggregates.operators[*o], aggregates.parts[*p].clone()))
+            .collect()
+    }
+
+    pub fn unique_operators(&self) -> HashSet<u32> {
+        self.aggregates.operators.iter().copied().collect()
+    }
+
+    pub fn unique_trials(&self) -> HashSet<u32> {
+        let mut trials = HashSet::new();
+        for i in self.data.iter() {
+            trials.insert(i.trial);
+        }
+        trials
+    }
+
+    pub fn ss_total(&self) -> f64 {
+        let mut sum = 0.0;
+        for data in self.data.iter() {
+            sum += (data.value - self.mean).powi(2);
+        }
+        sum
+    }
+
+    pub fn ss_operator(&self) -> f64 {
+        Aggregates::ss_between(&self.aggregates.operator, self.mean)
+    }
+
+    /// Variation of the part means around the mean of the operator who
+    /// measured them.
+    pub fn ss_part(&self) -> f64 {
+        let aggregates = &self.aggregates;
+        aggregates
+            .cells
+            .iter()
+            .zip(aggregates.cell.iter())
+            .map(|((_, o), part)| {
+                part.count as f64 * (part.mean() - aggregates.operator[*o].mean()).powi(2)
+            })
+            .sum()
+    }
+
+    pub fn ss_repeatability(&self) -> f64 {
+        let cells = &self.aggregates.cell;
+        self.data
+            .iter()
+            .zip(self.aggregates.cell_of.iter())
+            .map(|(data, cell)| (data.value - cells[*cell].mean()).powi(2))
+            .sum()
+    }
+
+    fn varcomp_intervals(&self) -> Option<VarCompIntervals> {
+        if self.df_part == 0 || self.df_repeatability == 0 {
+            return None;
+        }
+        let repeatability = MeanSquareTerm::new(
+            "repeatability",
+            1.0,
+            self.ms_repeatability,
+            self.df_repeatability,
+        );
+        let part_term = MeanSquareTerm::new("part", 1.0, self.ms_part, self.df_part);
+        let n_trial = self.n_trial as f64;
+        let part = [
+            MeanSquareTerm {
+                coef: 1.0 / n_trial,
+                ..part_term
+            },
+            MeanSquareTerm {
+                coef: -1.0 / n_trial,
+                ..repeatability
+            },
+        ];
+        let divisor = (self.n_part * self.n_trial) as f64;
+        let operator = self.ms_operator.map(|mso| {
+            [
+                MeanSquareTerm::new("operator", 1.0 / divisor, mso, self.df_operator),
+                MeanSquareTerm {
+                    coef: -1.0 / divisor,
+                    ..part_term
+                },
+            ]
+        });
+        Some(VarCompIntervals::new(
+            self.confidence,
+            &part,
+            operator.as_ref().map(|o| o.as_slice()),
+            None,
+            repeatability,
+        ))
+    }
+
+    fn ms_operator(&self) -> Option<f64> {
+        if self.df_operator == 0 {
+            return None;
+        }
+        Some(self.ss_operator / self.df_operator as f64)
+    }
+}
+
+fn all_equal<I: Iterator<Item = u32>>(mut values: I) -> bool {
+    match values.next() {
+        Some(first) => values.all(|v| v == first),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    /// Three operators with three parts each, every part measured twice. Part
+    /// labels are reused between operators.
+    const MEASUREMENTS: [[[f64; 2]; 3]; 3] = [
+        [[10.1, 10.3], [9.8, 9.9], [10.5, 10.4]],
+        [[10.6, 10.4], [10.0, 10.2], [10.9, 11.0]],
+        [[9.9, 10.0], [9.5, 9.7], [10.2, 10.4]],
+    ];
+
+    /// The study data, with the trial label of every measurement.
+    fn study_data(trial: impl Fn(usize, usize, usize) -> u32) -> Vec<GageStudyData> {
+        let mut data = Vec::new();
+        for (operator, parts) in MEASUREMENTS.iter().enumerate() {
+            for (part, values) in parts.iter().enumerate() {
+                for (replicate, value) in values.iter().enumerate() {
+                    data.push(GageStudyData::new(
+                        &(part + 1).to_string(),
+                        operator as u32 + 1,
+                        trial(operator, part, replicate),
+                        *value,
+                    ));
+                }
+            }
+        }
+        data
+    }
+
+    fn assert_reference_values(study: &NestedGageStudyDataSet) {
+        assert_eq!((study.n_operator, study.n_part, study.n_trial), (3, 3, 2));
+        assert_eq!(
+            (study.df_operator, study.df_part, study.df_repeatability),
+            (2, 6, 9)
+        );
+        assert_close(study.ss_operator, 0.981111, 1e-6);
+        assert_close(study.ss_part, 1.576667, 1e-6);
+        assert_close(study.ss_repeatability, 0.12, 1e-9);
+        assert_close(study.ss_total, 2.677778, 1e-6);
+        assert_close(study.f_operator.unwrap(), 1.866808, 1e-6);
+        assert_close(study.f_part, 19.708333, 1e-6);
+        assert_close(study.varcomp_part, 0.124722, 1e-6);
+        assert_close(study.varcomp_operator.unwrap(), 0.037963, 1e-6);
+        assert_close(study.varcomp_repeatability, 0.013333, 1e-6);
+        assert_close(study.varcomp_total_gagerr, 0.051296, 1e-6);
+        assert_close(study.varcomp_total, 0.176019, 1e-6);
+    }
+
+    #[test]
+    fn anova_matches_reference_values() {
+        let study = NestedGageStudyDataSet::new_with_data(study_data(|_, _, r| r as u32 + 1))
+            .calculate()
+            .unwrap();
+        assert_reference_values(&study);
+    }
+
+    #[test]
+    fn replicates_are_counted_per_part_whatever_the_trial_labels() {
+        // Trials numbered on from one part to the next, as a lab log would
+        let study = NestedGageStudyDataSet::new_with_data(study_data(|o, p, r| {
+            (o * 6 + p * 2 + r) as u32 + 1
+        }))
+        .calculate()
+        .unwrap();
+        assert_eq!(study.trials.len(), 18);
+        assert_reference_values(&study);
+    }
+
+    #[test]
+    fn single_measurement_per_part_is_rejected() {
+        let data = study_data(|_, _, r| r as u32 + 1)
+            .into_iter()
+            .filter(|d| d.trial == 1)
+            .collect();
+        let error = NestedGageStudyDataSet::new_with_data(data)
+            .calculate()
+            .err();
+        assert_eq!(
+            error,
+            Some(GageError::TooFewLevels {
+                factor: "Trial".to_owned(),
+                found: 1,
+                required: 2,
+            })
+        );
+    }
+}
@@ -0,0 +1,394 @@
+use crate::aggregates::Aggregates;
+
+/// Restricted maximum likelihood estimates of the variance components of the
+/// crossed random effects model with parts, operators and their interaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reml {
+    /// Part, operator and part * operator variances, only the part variance
+    /// when there is a single operator
+    pub varcomps: Vec<f64>,
+    pub error: f64,
+    pub iterations: u32,
+    pub converged: bool,
+}
+
+const MAX_ITER: u32 = 2000;
+/// Relative size of the simplex, over the square roots of the variance
+/// ratios, at which the search stops
+const TOLERANCE: f64 = 1.0e-9;
+/// Relative rounding error of the likelihood
+const NOISE: f64 = 1.0e-13;
+
+/// Estimates the variance components by maximizing the REML likelihood.
+///
+/// Measurements only enter the likelihood through the mean and count of
+/// every part/operator cell and the sum of squares within the cells,
+/// `ss_within`. The cell means have covariance `V = D + Z G Z'` with `D`
+/// diagonal, and the part block of `I + G½ Z'D⁻¹Z G½` is diagonal too, so
+/// the likelihood takes O(parts × operators²) to evaluate whatever the
+/// number of measurements. The error variance is profiled out and the
+/// ratios of the other components to it are found by Nelder-Mead over their
+/// square roots, which keeps them non-negative.
+///
+/// `start` holds initial estimates in the order of `Reml::varcomps`, then
+/// the error variance; negative ones are taken as zero. Returns `None` if
+/// the likelihood cannot be evaluated, e.g. when all cells are constant.
+pub fn reml(aggregates: &Aggregates, ss_within: f64, start: &[f64]) -> Option<Reml> {
+    let model = CellMeans::new(aggregates, ss_within);
+    let components = if model.crossed { 3 } else { 1 };
+    if start.len() != components + 1 {
+        return None;
+    }
+    let error = start[components].max(f64::MIN_POSITIVE);
+    let x0: Vec<f64> = start[..components]
+        .iter()
+        .map(|v| (v.max(0.0) / error).sqrt())
+        .collect();
+    let deviance = |x: &[f64]| {
+        let ratios: Vec<f64> = x.iter().map(|x| x * x).collect();
+        model.deviance(&ratios).map_or(f64::INFINITY, |(d, _)| d)
+    };
+    if !deviance(&x0).is_finite() {
+        return None;
+    }
+
+    // A restart from the optimum guards against a collapsed simplex
+    let (x, first, converged) = nelder_mead(&deviance, x0, MAX_ITER);
+    let (x, second, restarted) = nelder_mead(&deviance, x, MAX_ITER);
+    let x = newton(&deviance, x);
+    let ratios: Vec<f64> = x.iter().map(|x| x * x).collect();
+    let (_, error) = model.deviance(&ratios)?;
+    Some(Reml {
+        varcomps: ratios.iter().map(|r| r * error).collect(),
+        error,
+        iterations: first + second,
+        converged: converged && restarted,
+    })
+}
+
+/// Sufficient statistics of the cell means model.
+struct CellMeans {
+    /// Whether there is more than one operator, so that the operator and
+    /// interaction effects are in the model
+    crossed: bool,
+    n_part: usize,
+    n_operator: usize,
+    /// Part, operator, count and mean of every cell, sorted by part
+    cells: Vec<(usize, usize, f64, f64)>,
+    /// Cells of every part, as ranges of `cells`
+    part_cells: Vec<std::ops::Range<usize>>,
+    ss_within: f64,
+    /// Degrees of freedom of the REML likelihood, one less than the number
+    /// of measurements
+    df: f64,
+}
+
+impl CellMeans {
+    fn new(aggregates: &Aggregates, ss_within: f64) -> Self {
+        let mut cells: Vec<(usize, usize, f64, f64)> = aggregates
+            .cells
+            .iter()
+            .zip(aggregates.cell.iter())
+            .map(|((part, operator), tally)| (*part, *operator, tally.count as f64, tally.mean()))
+            .collect();
+        cells.sort_by_key(|(part, _, _, _)| *part);
+        let n_part = aggregates.parts.len();
+        let part_cells = (0..n_part)
+            .map(|p| cells.partition_point(|c| c.0 < p)..cells.partition_point(|c| c.0 <= p))
+            .collect();
+        Self {
+            crossed: aggregates.operators.len() > 1,
+            n_part,
+            n_operator: aggregates.operators.len(),
+            cells,
+            part_cells,
+            ss_within,
+            df: aggregates.total.count as f64 - 1.0,
+        }
+    }
+
+    /// −2 × the REML log likelihood, up to a constant, with the error
+    /// variance profiled out, and that error variance. `ratios` are the
+    /// other components divided by the error variance.
+    fn deviance(&self, ratios: &[f64]) -> Option<(f64, f64)> {
+        let (part, operator, interaction) = match ratios {
+            [part, operator, interaction] => (*part, *operator, *interaction),
+            [part] => (*part, 0.0, 0.0),
+            _ => return None,
+        };
+        let n_operator = if self.crossed { self.n_operator } else { 0 };
+
+        // D⁻¹ weights of the cells and their sums by part and operator
+        let mut log_det = 0.0;
+        let mut weights = Vec::with_capacity(self.cells.len());
+        let mut part_weight = vec![0.0; self.n_part];
+        let mut part_sum = vec![0.0; self.n_part];
+        let mut operator_weight = vec![0.0; n_operator];
+        let mut operator_sum = vec![0.0; n_operator];
+        let (mut s11, mut s1y, mut syy) = (0.0, 0.0, 0.0);
+        for (p, o, count, mean) in self.cells.iter() {
+            let d = interaction + 1.0 / count;
+            log_det += d.ln();
+            let w = 1.0 / d;
+            weights.push(w);
+            part_weight[*p] += w;
+            part_sum[*p] += w * mean;
+            if self.crossed {
+                operator_weight[*o] += w;
+                operator_sum[*o] += w * mean;
+            }
+            s11 += w;
+            s1y += w * mean;
+            syy += w * mean * mean;
+        }
+
+        // M = I + G½ Z'D⁻¹Z G½ split into its diagonal part block, the
+        // operator block and the part/operator block between them
+        let m_part: Vec<f64> = part_weight.iter().map(|w| 1.0 + part * w).collect();
+        // Operator and part/operator entry of every cell of a part
+        let scale = if self.crossed {
+            (part * operator).sqrt()
+        } else {
+            0.0
+        };
+        let (cells, weights) = (&self.cells, &weights);
+        let between = |p: usize| {
+            self.part_cells[p]
+                .clone()
+                .map(move |c| (cells[c].1, scale * weights[c]))
+        };
+        // Schur complement of the part block
+        let mut schur = vec![vec![0.0; n_operator]; n_operator];
+        for (o, w) in operator_weight.iter().enumerate() {
+            schur[o][o] = 1.0 + operator * w;
+        }
+        if self.crossed {
+            for (p, m) in m_part.iter().enumerate() {
+                for (a, wa) in between(p) {
+                    for (b, wb) in between(p) {
+                        schur[a][b] -= wa * wb / m;
+                    }
+                }
+            }
+        }
+        let schur = Cholesky::new(schur)?;
+        log_det += m_part.iter().map(|m| m.ln()).sum::<f64>() + schur.log_det();
+
+        // v'V⁻¹w = v'D⁻¹w − r_v'M⁻¹r_w with r = G½ Z'D⁻¹ v
+        let solve = |r_part: &[f64], r_operator: &[f64]| {
+            let mut rhs = r_operator.to_vec();
+            for (p, (m, r)) in m_part.iter().zip(r_part.iter()).enumerate() {
+                for (o, w) in between(p) {
+                    rhs[o] -= w * r / m;
+                }
+            }
+            let x_operator = schur.solve(rhs);
+            let x_part: Vec<f64> = m_part
+                .iter()
+                .zip(r_part.iter())
+                .enumerate()
+                .map(|(p, (m, r))| {
+                    (r - between(p).map(|(o, w)| w * x_operator[o]).sum::<f64>()) / m
+                })
+                .collect();
+            (x_part, x_operator)
+        };
+        let r = |sums: &[f64], variance: f64| -> Vec<f64> {
+            sums.iter().map(|s| variance.sqrt() * s).collect()
+        };
+        let (r1_part, r1_operator) = (r(&part_weight, part), r(&operator_weight, operator));
+        let (ry_part, ry_operator) = (r(&part_sum, part), r(&operator_sum, operator));
+        let (x1_part, x1_operator) = solve(&r1_part, &r1_operator);
+        let dot = |a: &[f64], b: &[f64]| a.iter().zip(b.iter()).map(|(a, b)| a * b).sum::<f64>();
+        let s11 = s11 - dot(&r1_part, &x1_part) - dot(&r1_operator, &x1_operator);
+        let s1y = s1y - dot(&ry_part, &x1_part) - dot(&ry_operator, &x1_operator);
+        let (xy_part, xy_operator) = solve(&ry_part, &ry_operator);
+        let syy = syy - dot(&ry_part, &xy_part) - dot(&ry_operator, &xy_operator);
+
+        let q = syy - s1y * s1y / s11 + self.ss_within;
+        if !(q > 0.0 && s11 > 0.0) {
+            return None;
+        }
+        let error = q / self.df;
+        let deviance = self.df * error.ln() + log_det + s11.ln();
+        deviance.is_finite().then_some((deviance, error))
+    }
+}
+
+/// Cholesky factor of a small symmetric positive definite matrix.
+pub(crate) struct Cholesky {
+    lower: Vec<Vec<f64>>,
+}
+
+impl Cholesky {
+    /// `None` when `a` is not positive definite.
+    pub(crate) fn new(a: Vec<Vec<f64>>) -> Option<Self> {
+        let n = a.len();
+        let mut lower = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in 0..=i {
+                let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+                if i == j {
+                    let d = a[i][i] - sum;
+                    if d.is_nan() || d <= 0.0 {
+                        return None;
+                    }
+                    lower[i][i] = d.sqrt();
+                } else {
+                    lower[i][j] = (a[i][j] - sum) / lower[j][j];
+                }
+            }
+        }
+        Some(Self { lower })
+    }
+
+    fn log_det(&self) -> f64 {
+        2.0 * self
+            .lower
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row[i].ln())
+            .sum::<f64>()
+    }
+
+    pub(crate) fn solve(&self, mut b: Vec<f64>) -> Vec<f64> {
+        let n = b.len();
+        for i in 0..n {
+            b[i] = (b[i] - (0..i).map(|k| self.lower[i][k] * b[k]).sum::<f64>()) / self.lower[i][i];
+        }
+        for i in (0..n).rev() {
+            b[i] = (b[i] - (i + 1..n).map(|k| self.lower[k][i] * b[k]).sum::<f64>())
+                / self.lower[i][i];
+        }
+        b
+    }
+}
+
+/// Minimum of `f` by the Nelder-Mead simplex method, the number of
+/// iterations and whether it converged within `max_iter`.
+fn nelder_mead(
+    f: &impl Fn(&[f64]) -> f64,
+    start: Vec<f64>,
+    max_iter: u32,
+) -> (Vec<f64>, u32, bool) {
+    let n = start.len();
+    let mut simplex = vec![start.clone()];
+    for i in 0..n {
+        let mut vertex = start.clone();
+        vertex[i] += (0.1 * vertex[i].abs()).max(0.1);
+        simplex.push(vertex);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|v| f(v)).collect();
+    let point = |from: &[f64], towards: &[f64], t: f64| -> Vec<f64> {
+        from.iter()
+            .zip(towards.iter())
+            .map(|(a, b)| a + t * (b - a))
+            .collect()
+    };
+    for iteration in 1..=max_iter {
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+        simplex = order.iter().map(|i| simplex[*i].clone()).collect();
+        values = order.iter().map(|i| values[*i]).collect();
+
+        let best = &simplex[0];
+        let size = simplex[1..]
+            .iter()
+            .flat_map(|v| v.iter().zip(best.iter()).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        let spread = values[n] - values[0];
+        // Either the simplex is small, or it is down to the rounding noise
+        // of the likelihood, which the Newton steps take care of
+        if size <= TOLERANCE * best.iter().fold(1.0_f64, |m, x| m.max(x.abs()))
+            || spread <= NOISE * values[0].abs().max(1.0)
+        {
+            return (simplex.swap_remove(0), iteration, true);
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|v| v[j]).sum::<f64>() / n as f64)
+            .collect();
+        let reflected = point(&centroid, &simplex[n], -1.0);
+        let fr = f(&reflected);
+        if fr < values[0] {
+            let expanded = point(&centroid, &simplex[n], -2.0);
+            let fe = f(&expanded);
+            (simplex[n], values[n]) = if fe < fr {
+                (expanded, fe)
+            } else {
+                (reflected, fr)
+            };
+        } else if fr < values[n - 1] {
+            (simplex[n], values[n]) = (reflected, fr);
+        } else {
+            let contracted = if fr < values[n] {
+                point(&centroid, &reflected, 0.5)
+            } else {
+                point(&centroid, &simplex[n], 0.5)
+            };
+            let fc = f(&contracted);
+            if fc < values[n].min(fr) {
+                (simplex[n], values[n]) = (contracted, fc);
+            } else {
+                for i in 1..=n {
+                    simplex[i] = point(&simplex[0], &simplex[i], 0.5);
+                    values[i] = f(&simplex[i]);
+                }
+            }
+        }
+    }
+    let best = (0..=n)
+        .min_by(|a, b| values[*a].total_cmp(&values[*b]))
+        .unwrap_or(0);
+    (simplex.swap_remove(best), max_iter, false)
+}
+
+/// Refines a minimum of `f` with Newton steps on central difference
+/// derivatives. Near the minimum the likelihood is too flat for its values
+/// to tell points apart, so steps are kept while they shrink the gradient and
+/// do not raise `f` by more than its rounding noise.
+fn newton(f: &impl Fn(&[f64]) -> f64, mut x: Vec<f64>) -> Vec<f64> {
+    let n = x.len();
+    let derivatives = |x: &[f64]| {
+        let fx = f(x);
+        let h: Vec<f64> = x.iter().map(|x| 1.0e-4 * x.abs().max(1.0e-2)).collect();
+        let at = |steps: &[(usize, f64)]| {
+            let mut y = x.to_vec();
+            for (i, s) in steps.iter() {
+                y[*i] += s * h[*i];
+            }
+            f(&y)
+        };
+        let mut gradient = vec![0.0; n];
+        let mut hessian = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            let (plus, minus) = (at(&[(i, 1.0)]), at(&[(i, -1.0)]));
+            gradient[i] = (plus - minus) / (2.0 * h[i]);
+            hessian[i][i] = (plus - 2.0 * fx + minus) / (h[i] * h[i]);
+            for j in 0..i {
+                let d = at(&[(i, 1.0), (j, 1.0)])
+                    - at(&[(i, 1.0), (j, -1.0)])
+                    - at(&[(i, -1.0), (j, 1.0)])
+                    + at(&[(i, -1.0), (j, -1.0)]);
+                hessian[i][j] = d / (4.0 * h[i] * h[j]);
+                hessian[j][i] = hessian[i][j];
+            }
+        }
+        (fx, gradient, hessian)
+    };
+    let norm = |g: &[f64]| g.iter().map(|g| g * g).sum::<f64>().sqrt();
+    let (mut fx, mut gradient, mut hessian) = derivatives(&x);
+    for _ in 0..10 {
+        let step = match Cholesky::new(hessian.clone()) {
+            Some(c) => c.solve(gradient.clone()),
+            None => return x,
+        };
+        let y: Vec<f64> = x.iter().zip(step.iter()).map(|(x, s)| x - s).collect();
+        let (fy, gy, hy) = derivatives(&y);
+        if fy > fx + 1.0e-12 * fx.abs().max(1.0) || norm(&gy) >= norm(&gradient) {
+            return x;
+        }
+        (x, fx, gradient, hessian) = (y, fy, gy, hy);
+    }
+    x
+}
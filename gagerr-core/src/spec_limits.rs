@@ -0,0 +1,29 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Specification of the characteristic being measured, as two-sided limits,
+/// a tolerance width, or a single limit.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SpecLimits {
+    pub lsl: Option<f64>,
+    pub usl: Option<f64>,
+    /// Tolerance width, used when both limits are not given
+    pub width: Option<f64>,
+}
+
+impl SpecLimits {
+    /// Tolerance the study variation is compared with. A one-sided limit
+    /// gives twice the distance from the process mean to the limit, so that
+    /// half the study variation is compared with that distance.
+    pub fn tolerance(&self, mean: f64) -> Option<f64> {
+        let tolerance = match (self.lsl, self.usl, self.width) {
+            (Some(lsl), Some(usl), _) => usl - lsl,
+            (_, _, Some(width)) => width,
+            (None, Some(usl), None) => 2.0 * (usl - mean),
+            (Some(lsl), None, None) => 2.0 * (mean - lsl),
+            (None, None, None) => return None,
+        };
+        (tolerance > 0.0).then_some(tolerance)
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Layout of a gage study and therefore which analysis applies to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StudyType {
+    /// Every operator measures every part.
+    #[default]
+    Crossed,
+    /// Each operator measures their own parts, e.g. destructive testing.
+    Nested,
+    /// Crossed study with additional factors such as fixture or gage.
+    Expanded,
+    /// Repeated measurements of a single reference standard.
+    Type1,
+    /// Reference parts across the operating range, for linearity and bias.
+    Linearity,
+    /// Categorical ratings, e.g. pass/fail or visual grades.
+    Attribute,
+}
+
+impl StudyType {
+    pub const ALL: [StudyType; 6] = [
+        StudyType::Crossed,
+        StudyType::Nested,
+        StudyType::Expanded,
+        StudyType::Type1,
+        StudyType::Linearity,
+        StudyType::Attribute,
+    ];
+}
+
+impl fmt::Display for StudyType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StudyType::Crossed => write!(f, "Crossed"),
+            StudyType::Nested => write!(f, "Nested"),
+            StudyType::Expanded => write!(f, "Expanded"),
+            StudyType::Type1 => write!(f, "Type 1"),
+            StudyType::Linearity => write!(f, "Linearity and Bias"),
+            StudyType::Attribute => write!(f, "Attribute Agreement"),
+        }
+    }
+}
+
+impl FromStr for StudyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.to_string() == s)
+            .ok_or_else(|| format!("Unknown study type: {s}"))
+    }
+}
+
+/// Which method a crossed study is evaluated with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisMethod {
+    #[default]
+    Anova,
+    /// AIAG Average and Range method
+    Range,
+    Both,
+}
+
+impl AnalysisMethod {
+    pub const ALL: [AnalysisMethod; 3] = [
+        AnalysisMethod::Anova,
+        AnalysisMethod::Range,
+        AnalysisMethod::Both,
+    ];
+}
+
+impl fmt::Display for AnalysisMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnalysisMethod::Anova => write!(f, "ANOVA"),
+            AnalysisMethod::Range => write!(f, "Average and Range"),
+            AnalysisMethod::Both => write!(f, "Both"),
+        }
+    }
+}
+
+impl FromStr for AnalysisMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.to_string() == s)
+            .ok_or_else(|| format!("Unknown analysis method: {s}"))
+    }
+}
+
+/// How the variance components of a crossed study are estimated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum VarCompMethod {
+    /// Expected mean squares, only valid for balanced designs
+    #[default]
+    Anova,
+    /// Restricted maximum likelihood, also valid for unbalanced designs
+    Reml,
+}
+
+impl VarCompMethod {
+    pub const ALL: [VarCompMethod; 2] = [VarCompMethod::Anova, VarCompMethod::Reml];
+}
+
+impl fmt::Display for VarCompMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VarCompMethod::Anova => write!(f, "ANOVA"),
+            VarCompMethod::Reml => write!(f, "REML"),
+        }
+    }
+}
+
+impl FromStr for VarCompMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.to_string() == s)
+            .ok_or_else(|| format!("Unknown variance component method: {s}"))
+    }
+}
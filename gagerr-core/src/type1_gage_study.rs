@@ -0,0 +1,171 @@
+use crate::gage_study_data::GageStudyData;
+use crate::stats;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Inputs of a Type 1 study that do not come from the measurements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Type1Settings {
+    /// Certified value of the master being measured
+    pub reference: f64,
+    /// Tolerance width of the characteristic
+    pub tolerance: f64,
+    /// Percentage of the tolerance the gage may use, usually 20
+    pub k_percent: f64,
+    /// Number of standard deviations of the process spread, usually 6
+    pub l: f64,
+}
+
+impl Default for Type1Settings {
+    fn default() -> Self {
+        Self {
+            reference: 0.0,
+            tolerance: 0.0,
+            k_percent: 20.0,
+            l: 6.0,
+        }
+    }
+}
+
+/// Type 1 gage study: one operator measures a reference standard many times
+/// to assess bias and repeatability against the tolerance.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Type1GageStudy {
+    pub data: Vec<GageStudyData>,
+    pub settings: Type1Settings,
+    pub parts: HashSet<String>,
+    pub n: u32,
+    pub mean: f64,
+    pub stddev: f64,
+    pub bias: f64,
+    pub t: f64,
+    pub p_bias: f64,
+    pub cg: f64,
+    pub cgk: f64,
+    /// Percentage of the allowed tolerance used by repeatability
+    pub var_repeatability: f64,
+    /// Percentage of the allowed tolerance used by repeatability and bias
+    pub var_repeatability_bias: f64,
+}
+
+impl Default for Type1GageStudy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Type1GageStudy {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::<GageStudyData>::new(),
+            settings: Type1Settings::default(),
+            parts: HashSet::new(),
+            n: 0,
+            mean: 0.0,
+            stddev: 0.0,
+            bias: 0.0,
+            t: 0.0,
+            p_bias: 0.0,
+            cg: 0.0,
+            cgk: 0.0,
+            var_repeatability: 0.0,
+            var_repeatability_bias: 0.0,
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>, settings: Type1Settings) -> Self {
+        let mut study = Self::new();
+        study.data = data;
+        study.settings = settings;
+        study
+    }
+
+    pub fn calculate_mut(&mut self) -> &mut Self {
+        let settings = self.settings;
+        self.parts = self.data.iter().map(|d| d.part.clone()).collect();
+        self.n = self.data.len() as u32;
+        let n = self.n as f64;
+        self.mean = self.data.iter().map(|d| d.value).sum::<f64>() / n;
+        self.stddev = (self
+            .data
+            .iter()
+            .map(|d| (d.value - self.mean).powi(2))
+            .sum::<f64>()
+            / (n - 1.0))
+            .sqrt();
+        self.bias = self.mean - settings.reference;
+        self.t = self.bias.abs() / (self.stddev / n.sqrt());
+        self.p_bias = stats::t_sf_two_sided(self.t, n - 1.0);
+
+        let allowed = settings.k_percent / 100.0 * settings.tolerance;
+        self.cg = allowed / (settings.l * self.stddev);
+        self.cgk = (allowed / 2.0 - self.bias.abs()) / (settings.l / 2.0 * self.stddev);
+        self.var_repeatability = settings.k_percent / self.cg;
+        self.var_repeatability_bias = settings.k_percent / self.cgk;
+        self
+    }
+
+    pub fn calculate(mut self) -> Self {
+        self.calculate_mut();
+        self
+    }
+
+    /// Measurements in the order they were taken.
+    pub fn values(&self) -> Vec<f64> {
+        self.data.iter().map(|d| d.value).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} differs from {expected} by more than {tolerance}"
+        );
+    }
+
+    fn study(values: &[f64]) -> Type1GageStudy {
+        let data = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| GageStudyData::new("Master", 1, i as u32 + 1, *v))
+            .collect();
+        let settings = Type1Settings {
+            reference: 10.0,
+            tolerance: 1.0,
+            ..Default::default()
+        };
+        Type1GageStudy::new_with_data(data, settings).calculate()
+    }
+
+    #[test]
+    fn capability_matches_reference_values() {
+        let study = study(&[
+            10.01, 9.99, 10.02, 10.00, 9.98, 10.03, 10.01, 10.00, 9.99, 10.02,
+        ]);
+        assert_eq!(study.n, 10);
+        assert_close(study.mean, 10.005, 1e-12);
+        assert_close(study.stddev, 0.015811, 1e-6);
+        assert_close(study.bias, 0.005, 1e-12);
+        assert_close(study.t, 1.0, 1e-9);
+        assert_close(study.p_bias, 0.343436, 1e-6);
+        assert_close(study.cg, 2.108185, 1e-6);
+        assert_close(study.cgk, 2.002776, 1e-6);
+        assert_close(study.var_repeatability, 9.486833, 1e-6);
+        assert_close(study.var_repeatability_bias, 9.986140, 1e-6);
+    }
+
+    #[test]
+    fn bias_lowers_cgk_but_not_cg() {
+        let centred = study(&[9.99, 10.01, 10.0, 9.98, 10.02]);
+        let shifted = study(&[10.04, 10.06, 10.05, 10.03, 10.07]);
+        assert_close(centred.cg, shifted.cg, 1e-9);
+        assert_close(centred.cgk, centred.cg, 1e-9);
+        assert!(shifted.cgk < shifted.cg);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::aggregates::Aggregates;
+use crate::constants;
+use crate::gage_error::{check_levels, check_measurements, GageError};
+use crate::gage_study_data::GageStudyData;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Trials of one part by one operator.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Subgroup {
+    pub part: String,
+    pub operator: u32,
+    pub mean: f64,
+    pub range: f64,
+}
+
+/// Center line and control limits of a chart.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ControlLimits {
+    pub center: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ControlLimits {
+    pub fn contains(&self, value: f64) -> bool {
+        (self.lower..=self.upper).contains(&value)
+    }
+}
+
+/// Xbar and R charts of a crossed study, with a subgroup per part and
+/// operator. The R chart shows whether repeatability is consistent; on the
+/// Xbar chart most averages should fall outside the limits, which are based
+/// on repeatability alone, if the gage can tell the parts apart.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct XbarRChartData {
+    pub data: Vec<GageStudyData>,
+    /// Subgroups by operator, then by part in order of appearance
+    pub subgroups: Vec<Subgroup>,
+    /// Most common number of trials per subgroup, which the limits are
+    /// based on
+    pub subgroup_size: u32,
+    pub xbar_limits: ControlLimits,
+    pub range_limits: ControlLimits,
+}
+
+impl Default for XbarRChartData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XbarRChartData {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            subgroups: Vec::new(),
+            subgroup_size: 0,
+            xbar_limits: ControlLimits::default(),
+            range_limits: ControlLimits::default(),
+        }
+    }
+
+    pub fn new_with_data(data: Vec<GageStudyData>) -> Self {
+        let mut chart = Self::new();
+        chart.data = data;
+        chart
+    }
+
+    pub fn calculate_mut(&mut self) -> Result<&mut Self, GageError> {
+        check_measurements(&self.data)?;
+        let aggregates = Aggregates::new(&self.data);
+
+        let mut counts: BTreeMap<u32, usize> = BTreeMap::new();
+        for cell in aggregates.cell.iter() {
+            *counts.entry(cell.count).or_default() += 1;
+        }
+        // Ties go to the larger number of trials
+        self.subgroup_size = counts
+            .iter()
+            .max_by_key(|(trials, cells)| (**cells, **trials))
+            .map_or(0, |(trials, _)| *trials);
+        check_levels("Trial", self.subgroup_size as usize, 2)?;
+        let (a2, (d3, d4)) = match (
+            constants::a2(self.subgroup_size),
+            constants::d3_d4(self.subgroup_size),
+        ) {
+            (Some(a2), Some(d)) => (a2, d),
+            _ => {
+                return Err(GageError::TooManyLevels {
+                    factor: "Trial".to_owned(),
+                    found: self.subgroup_size as usize,
+                    allowed: 15,
+                })
+            }
+        };
+
+        let mut cells: Vec<usize> = (0..aggregates.cells.len()).collect();
+        cells.sort_by_key(|c| {
+            let (part, operator) = aggregates.cells[*c];
+            (aggregates.operators[operator], part)
+        });
+        self.subgroups = cells
+            .into_iter()
+            .map(|c| {
+                let (part, operator) = aggregates.cells[c];
+                Subgroup {
+                    part: aggregates.parts[part].clone(),
+                    operator: aggregates.operators[operator],
+                    mean: aggregates.cell[c].mean(),
+                    range: aggregates.cell[c].range(),
+                }
+            })
+            .collect();
+
+        let n = self.subgroups.len() as f64;
+        let r_bar = self.subgroups.iter().map(|s| s.range).sum::<f64>() / n;
+        let x_bar = self.subgroups.iter().map(|s| s.mean).sum::<f64>() / n;
+        self.range_limits = ControlLimits {
+            center: r_bar,
+            lower: d3 * r_bar,
+            upper: d4 * r_bar,
+        };
+        self.xbar_limits = ControlLimits {
+            center: x_bar,
+            lower: x_bar - a2 * r_bar,
+            upper: x_bar + a2 * r_bar,
+        };
+        Ok(self)
+    }
+
+    pub fn calculate(mut self) -> Result<Self, GageError> {
+        self.calculate_mut()?;
+        Ok(self)
+    }
+}
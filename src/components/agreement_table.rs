@@ -0,0 +1,142 @@
+use gagerr_core::attribute_agreement::{Agreement, KappaRow, KendallRow};
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct AgreementTableProps {
+    pub title: String,
+    /// Agreement, kappa and Kendall rows, each labelled by appraiser
+    pub agreement: Vec<(String, Agreement)>,
+    pub kappa_title: String,
+    pub kappa: Vec<(String, Vec<KappaRow>)>,
+    pub kendall_title: String,
+    pub kendall: Vec<(String, KendallRow)>,
+}
+
+pub struct AgreementTable;
+
+impl Component for AgreementTable {
+    type Message = ();
+    type Properties = AgreementTableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let agreement_row = |(label, a): &(String, Agreement)| {
+            html! {
+                <tr>
+                    <td>{label}</td>
+                    <td>{a.inspected}</td>
+                    <td>{a.matched}</td>
+                    <td>{format!("{:>-.2}%", a.percent)}</td>
+                    <td>{format!("({:.2}%, {:.2}%)", a.ci_lower, a.ci_upper)}</td>
+                </tr>
+            }
+        };
+        let kappa_rows = |(label, rows): &(String, Vec<KappaRow>)| {
+            rows.iter()
+                .map(|k| {
+                    html! {
+                        <tr>
+                            <td>{label}</td>
+                            <td>{k.category.clone().unwrap_or_else(|| "Overall".to_owned())}</td>
+                            <td>{or_na(k.kappa.map(|kappa| format!("{:>-.6}", kappa)))}</td>
+                            <td>{or_na(k.se.map(|se| format!("{:>-.6}", se)))}</td>
+                            <td>{or_na(k.z.map(|z| format!("{:>-.4}", z)))}</td>
+                            <td>{or_na(k.p.map(|p| format!("{:>-.6}", p)))}</td>
+                            <td>
+                                {or_na(k.ci_lower.zip(k.ci_upper).map(|(lower, upper)| {
+                                    format!("({:.4}, {:.4})", lower, upper)
+                                }))}
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>()
+        };
+        let kendall_row = |(label, k): &(String, KendallRow)| {
+            html! {
+                <tr>
+                    <td>{label}</td>
+                    <td>{format!("{:>-.6}", k.coefficient)}</td>
+                    <td>{format!("{:>-.4}", k.statistic)}</td>
+                    <td>{k.df.map(|df| df.to_string()).unwrap_or_default()}</td>
+                    <td>{format!("{:>-.6}", k.p)}</td>
+                </tr>
+            }
+        };
+        let statistic = if props.kendall.iter().any(|(_, k)| k.df.is_some()) {
+            "Chi-Sq"
+        } else {
+            "Z"
+        };
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{&props.title}</h2></div>
+                if props.agreement.is_empty() {
+                    <p class="table-note">{"Not enough ratings for this assessment."}</p>
+                } else {
+                    <table class="studyvar-table">
+                        <thead>
+                            <tr>
+                                <th>{"Appraiser"}</th>
+                                <th>{"# Inspected"}</th>
+                                <th>{"# Matched"}</th>
+                                <th>{"Percent"}</th>
+                                <th>{"95% CI"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for props.agreement.iter().map(agreement_row) }
+                        </tbody>
+                    </table>
+                }
+                if !props.kappa.is_empty() {
+                    <h3>{&props.kappa_title}</h3>
+                    <table class="anova-table">
+                        <thead>
+                            <tr>
+                                <th>{"Appraiser"}</th>
+                                <th>{"Response"}</th>
+                                <th>{"Kappa"}</th>
+                                <th>{"SE Kappa"}</th>
+                                <th>{"Z"}</th>
+                                <th>{"P"}</th>
+                                <th>{"95% CI"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for props.kappa.iter().map(kappa_rows) }
+                        </tbody>
+                    </table>
+                }
+                if !props.kendall.is_empty() {
+                    <h3>{&props.kendall_title}</h3>
+                    <table class="anova-table">
+                        <thead>
+                            <tr>
+                                <th>{"Appraiser"}</th>
+                                <th>{"Coef"}</th>
+                                <th>{statistic}</th>
+                                <th>{"DF"}</th>
+                                <th>{"P"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for props.kendall.iter().map(kendall_row) }
+                        </tbody>
+                    </table>
+                }
+                </div>
+            </>
+        }
+    }
+}
+
+/// A value that may not be defined, such as the kappa of a category that was
+/// never rated.
+fn or_na(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "n/a".to_owned())
+}
@@ -18,7 +18,17 @@ pub struct AnovaTableProps {
     pub f_part: f64,
     pub f_operator: Option<f64>,
     pub f_part_operator: Option<f64>,
-    pub p: f64,
+    pub p_part: f64,
+    pub p_operator: Option<f64>,
+    pub p_part_operator: Option<f64>,
+    pub title: String,
+    pub alpha_to_remove: Option<f64>,
+}
+
+impl Default for AnovaTableProps {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AnovaTableProps {
@@ -41,7 +51,11 @@ impl AnovaTableProps {
             f_part: 0.0,
             f_operator: None,
             f_part_operator: None,
-            p: 0.0,
+            p_part: 0.0,
+            p_operator: None,
+            p_part_operator: None,
+            title: "ANOVA".to_owned(),
+            alpha_to_remove: None,
         }
     }
 }
@@ -64,38 +78,48 @@ pub struct AnovaTable {
     pub f_part: f64,
     pub f_operator: Option<f64>,
     pub f_part_operator: Option<f64>,
-    pub p: f64,
+    pub p_part: f64,
+    pub p_operator: Option<f64>,
+    pub p_part_operator: Option<f64>,
+    pub title: String,
+    pub alpha_to_remove: Option<f64>,
 }
 
 impl Component for AnovaTable {
     type Message = ();
     type Properties = AnovaTableProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = ctx.props().clone();
         Self {
-            df_part: 0,
-            df_operator: 0,
-            df_part_operator: 0,
-            df_repeatability: 0,
-            df_total: 0,
-            ss_part: 0.0,
-            ss_operator: 0.0,
-            ss_part_operator: 0.0,
-            ss_repeatability: 0.0,
-            ss_total: 0.0,
-            ms_part: 0.0,
-            ms_operator: None,
-            ms_part_operator: None,
-            ms_repeatability: 0.0,
-            f_part: 0.0,
-            f_operator: None,
-            f_part_operator: None,
-            p: 0.0,
+            df_part: props.df_part,
+            df_operator: props.df_operator,
+            df_part_operator: props.df_part_operator,
+            df_repeatability: props.df_repeatability,
+            df_total: props.df_total,
+            ss_part: props.ss_part,
+            ss_operator: props.ss_operator,
+            ss_part_operator: props.ss_part_operator,
+            ss_repeatability: props.ss_repeatability,
+            ss_total: props.ss_total,
+            ms_part: props.ms_part,
+            ms_operator: props.ms_operator,
+            ms_part_operator: props.ms_part_operator,
+            ms_repeatability: props.ms_repeatability,
+            f_part: props.f_part,
+            f_operator: props.f_operator,
+            f_part_operator: props.f_part_operator,
+            p_part: props.p_part,
+            p_operator: props.p_operator,
+            p_part_operator: props.p_part_operator,
+            title: props.title,
+            alpha_to_remove: props.alpha_to_remove,
         }
     }
     fn changed(&mut self, ctx: &Context<Self>) -> bool {
         let props = ctx.props().clone();
         self.df_part = props.df_part;
+        self.df_operator = props.df_operator;
         self.df_part_operator = props.df_part_operator;
         self.df_repeatability = props.df_repeatability;
         self.df_total = props.df_total;
@@ -103,6 +127,7 @@ impl Component for AnovaTable {
         self.ss_operator = props.ss_operator;
         self.ss_part_operator = props.ss_part_operator;
         self.ss_repeatability = props.ss_repeatability;
+        self.ss_total = props.ss_total;
         self.ms_part = props.ms_part;
         self.ms_operator = props.ms_operator;
         self.ms_part_operator = props.ms_part_operator;
@@ -110,7 +135,11 @@ impl Component for AnovaTable {
         self.f_part = props.f_part;
         self.f_operator = props.f_operator;
         self.f_part_operator = props.f_part_operator;
-        self.p = props.p;
+        self.p_part = props.p_part;
+        self.p_operator = props.p_operator;
+        self.p_part_operator = props.p_part_operator;
+        self.title = props.title;
+        self.alpha_to_remove = props.alpha_to_remove;
         true
     }
 
@@ -122,7 +151,7 @@ impl Component for AnovaTable {
         html! {
             <>
                 <div class="data">
-                <div class="table-header"><h2>{"ANOVA"}</h2></div>
+                <div class="table-header"><h2>{&self.title}</h2></div>
                     <table class="anova-table">
                         <tr>
                             <th>{"Source"}</th>
@@ -138,24 +167,26 @@ impl Component for AnovaTable {
                             <td>{format!("{:>-.6}", self.ss_part)}</td>
                             <td>{format!("{:>-.6}", self.ms_part)}</td>
                             <td>{format!("{:>-.6}", self.f_part)}</td>
-                            <td>{format!("{:>-.6}", self.p)}</td>
+                            <td>{format!("{:>-.6}", self.p_part)}</td>
                         </tr>
-                        if self.df_operator > 2 {
+                        if self.df_operator > 0 {
                             <tr>
                                 <td>{"Operator"}</td>
                                 <td>{format!("{:>-.6}", self.df_operator)}</td>
                                 <td>{format!("{:>-.6}", self.ss_operator)}</td>
                                 <td>{format!("{:>-.6}", self.ms_operator.unwrap_or(0.0))}</td>
                                 <td>{format!("{:>-.6}", self.f_operator.unwrap_or(0.0))}</td>
-                                <td>{""}</td>
+                                <td>{format!("{:>-.6}", self.p_operator.unwrap_or(0.0))}</td>
                             </tr>
+                        }
+                        if self.df_part_operator > 0 {
                             <tr>
                                 <td>{"Parts * Operator"}</td>
                                 <td>{format!("{:>-.6}", self.df_part_operator)}</td>
                                 <td>{format!("{:>-.6}", self.ss_part_operator)}</td>
                                 <td>{format!("{:>-.6}", self.ms_part_operator.unwrap_or(0.0))}</td>
                                 <td>{format!("{:>-.6}", self.f_part_operator.unwrap_or(0.0))}</td>
-                                <td>{""}</td>
+                                <td>{format!("{:>-.6}", self.p_part_operator.unwrap_or(0.0))}</td>
                             </tr>
                         }
                         <tr>
@@ -175,6 +206,9 @@ impl Component for AnovaTable {
                             <td>{""}</td>
                         </tr>
                     </table>
+                    if let Some(alpha) = self.alpha_to_remove {
+                        <p class="table-note">{format!("α to remove interaction term = {}", alpha)}</p>
+                    }
                 </div>
             </>
         }
@@ -0,0 +1,63 @@
+/// Linear mapping from data values to SVG coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+    pub min: f64,
+    pub max: f64,
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Scale {
+    /// Scale covering `min..=max` with 5% padding on either side, drawn from
+    /// `start` to `end`. SVG y coordinates grow downwards, so pass the bottom
+    /// of the plot as `start` for a vertical axis.
+    pub fn padded(min: f64, max: f64, start: f64, end: f64) -> Self {
+        let (min, max) = if (max - min).abs() > f64::EPSILON * max.abs().max(1.0) {
+            (min, max)
+        } else {
+            (min - 0.5, max + 0.5)
+        };
+        let pad = (max - min) * 0.05;
+        Self {
+            min: min - pad,
+            max: max + pad,
+            start,
+            end,
+        }
+    }
+
+    /// Bounds of a set of values, ignoring any that are not finite.
+    pub fn bounds<I: IntoIterator<Item = f64>>(values: I) -> (f64, f64) {
+        values
+            .into_iter()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            })
+    }
+
+    pub fn map(&self, value: f64) -> f64 {
+        self.start + (value - self.min) / (self.max - self.min) * (self.end - self.start)
+    }
+
+    /// Round tick values, roughly `count` of them, within the scale.
+    pub fn ticks(&self, count: usize) -> Vec<f64> {
+        let raw = (self.max - self.min) / count.max(1) as f64;
+        if !raw.is_finite() || raw <= 0.0 {
+            return Vec::new();
+        }
+        let magnitude = 10f64.powf(raw.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .iter()
+            .map(|m| m * magnitude)
+            .find(|s| *s >= raw)
+            .unwrap_or(10.0 * magnitude);
+        let mut ticks = Vec::new();
+        let mut tick = (self.min / step).ceil() * step;
+        while tick <= self.max {
+            ticks.push(tick);
+            tick += step;
+        }
+        ticks
+    }
+}
@@ -0,0 +1,89 @@
+use crate::models::data_quality::DataQualityReport;
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct DataQualityPanelProps {
+    pub report: DataQualityReport,
+}
+
+pub struct DataQualityPanel;
+
+impl Component for DataQualityPanel {
+    type Message = ();
+    type Properties = DataQualityPanelProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let report = &ctx.props().report;
+        if report.is_clean() {
+            return html! {};
+        }
+        let join = |rows: &[u64]| {
+            rows.iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Data Quality"}</h2></div>
+                <p class="table-note warning">
+                    {format!(
+                        "Some lines do not fit a balanced study with {} trial(s) per part and operator. \
+                         Results below may be unreliable until they are fixed.",
+                        report.n_trial,
+                    )}
+                </p>
+                <table class="anova-table">
+                    <thead>
+                        <tr>
+                            <th>{"Line"}</th>
+                            <th>{"Part"}</th>
+                            <th>{"Operator"}</th>
+                            <th>{"Trial"}</th>
+                            <th>{"Issue"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for report.duplicates.iter().map(|d| {
+                            row(join(&d.rows), &d.part, Some(d.operator), Some(d.trial), "Duplicate trial".to_owned())
+                        })}
+                        { for report.extra_trials.iter().map(|t| {
+                            let issue = format!("Trial outside of 1 to {}", report.n_trial);
+                            row(t.row.map(|r| r.to_string()).unwrap_or_default(), &t.part, Some(t.operator), Some(t.trial), issue)
+                        })}
+                        { for report.missing_trials.iter().map(|t| {
+                            row(String::new(), &t.part, Some(t.operator), Some(t.trial), "Missing trial".to_owned())
+                        })}
+                        { for report.missing_cells.iter().map(|c| {
+                            let issue = "Part not measured by this operator".to_owned();
+                            row(String::new(), &c.part, Some(c.operator), None, issue)
+                        })}
+                    </tbody>
+                </table>
+                </div>
+            </>
+        }
+    }
+}
+
+fn row(
+    lines: String,
+    part: &str,
+    operator: Option<u32>,
+    trial: Option<u32>,
+    issue: String,
+) -> Html {
+    html! {
+        <tr>
+            <td>{lines}</td>
+            <td>{part}</td>
+            <td>{operator.map(|o| o.to_string()).unwrap_or_default()}</td>
+            <td>{trial.map(|t| t.to_string()).unwrap_or_default()}</td>
+            <td>{issue}</td>
+        </tr>
+    }
+}
@@ -0,0 +1,48 @@
+use gagerr_core::expanded_gage_study_data_set::AnovaRow;
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct ExpandedAnovaTableProps {
+    pub rows: Vec<AnovaRow>,
+}
+
+pub struct ExpandedAnovaTable;
+
+impl Component for ExpandedAnovaTable {
+    type Message = ();
+    type Properties = ExpandedAnovaTableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let fmt = |v: Option<f64>| v.map(|v| format!("{:>-.6}", v)).unwrap_or_default();
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Expanded ANOVA"}</h2></div>
+                    <table class="anova-table">
+                        <tr>
+                            <th>{"Source"}</th>
+                            <th>{"DF"}</th>
+                            <th>{"SS"}</th>
+                            <th>{"MS"}</th>
+                            <th>{"F"}</th>
+                            <th>{"P"}</th>
+                        </tr>
+                        { for ctx.props().rows.iter().map(|row| html! {
+                            <tr>
+                                <td>{&row.source}</td>
+                                <td>{row.df}</td>
+                                <td>{format!("{:>-.6}", row.ss)}</td>
+                                <td>{fmt(row.ms)}</td>
+                                <td>{fmt(row.f)}</td>
+                                <td>{fmt(row.p)}</td>
+                            </tr>
+                        })}
+                    </table>
+                </div>
+            </>
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::components::study_var_table::ndc_note;
+use crate::components::varcomp_table::truncated_note;
+use gagerr_core::confidence_interval::Interval;
+use gagerr_core::expanded_gage_study_data_set::VarCompRow;
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct ExpandedVarCompTableProps {
+    pub rows: Vec<VarCompRow>,
+    pub varcomp_total: f64,
+    pub study_var: f64,
+    pub ndc: u32,
+    pub tolerance: Option<f64>,
+    pub historical_stddev: Option<f64>,
+    pub stddev_part_process: Option<f64>,
+    pub confidence: f64,
+    pub ndc_interval: Option<Interval>,
+    pub truncated: Vec<String>,
+    pub confounded: Vec<String>,
+}
+
+pub struct ExpandedVarCompTable;
+
+impl Component for ExpandedVarCompTable {
+    type Message = ();
+    type Properties = ExpandedVarCompTableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let stddev_total = props.varcomp_total.sqrt();
+        // Part-to-part and total variation are recomputed from the historical
+        // standard deviation for % Process
+        let process_stddev = |row: &VarCompRow| match row.source.as_str() {
+            "Part-To-Part" => props.stddev_part_process.unwrap_or(row.stddev),
+            "Total Variation" => props.historical_stddev.unwrap_or(row.stddev),
+            _ => row.stddev,
+        };
+        let with_ci = props.rows.iter().any(|row| row.intervals.is_some());
+        let ci_header = format!("{:.0}% CI", props.confidence * 100.0);
+        let ci = |interval: Option<Interval>| {
+            interval
+                .map(|i| format!("({:.6}, {:.6})", i.lower, i.upper))
+                .unwrap_or_default()
+        };
+        let percent_ci = |interval: Option<Interval>| {
+            interval
+                .map(|i| i.scale(100.0))
+                .map(|i| format!("({:.2}%, {:.2}%)", i.lower, i.upper))
+                .unwrap_or_default()
+        };
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Variance Components"}</h2></div>
+                <table class="varcomp-table">
+                    <thead>
+                        <tr>
+                            <th>{"Source"}</th>
+                            <th>{"VarComp"}</th>
+                            if with_ci {
+                                <th>{&ci_header}</th>
+                            }
+                            <th>{"% Contribution"}</th>
+                            if with_ci {
+                                <th>{&ci_header}</th>
+                            }
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for props.rows.iter().map(|row| html! {
+                            <tr>
+                                <td class={classes!(row.indent.then_some("indent"))}>{&row.source}</td>
+                                <td>{format!("{:>-.6}", row.varcomp)}</td>
+                                if with_ci {
+                                    <td>{ci(row.intervals.map(|i| i.varcomp))}</td>
+                                }
+                                <td>{format!("{:>-.2}%", row.varcomp / props.varcomp_total * 100.0)}</td>
+                                if with_ci {
+                                    <td>{percent_ci(row.intervals.and_then(|i| i.contribution))}</td>
+                                }
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+                { truncated_note(&props.truncated) }
+                if !props.confounded.is_empty() {
+                    <p class="table-note">
+                        {format!(
+                            "Left out for having a single level within every level of the factor \
+                             they are nested in: {}. Their variation is included in that factor.",
+                            props.confounded.join(", "),
+                        )}
+                    </p>
+                }
+                </div>
+                <div class="data">
+                <div class="table-header"><h2>{"Study Variation"}</h2></div>
+                <table class="studyvar-table">
+                    <thead>
+                        <tr>
+                            <th>{"Source"}</th>
+                            <th>{"StdDev (SD)"}</th>
+                            if with_ci {
+                                <th>{&ci_header}</th>
+                            }
+                            <th>{format!("Study Var ({:.2} x SD)", props.study_var)}</th>
+                            <th>{"% Study Var"}</th>
+                            if with_ci {
+                                <th>{&ci_header}</th>
+                            }
+                            if props.historical_stddev.is_some() {
+                                <th>{"% Process"}</th>
+                            }
+                            if props.tolerance.is_some() {
+                                <th>{"% Tolerance"}</th>
+                            }
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for props.rows.iter().map(|row| html! {
+                            <tr>
+                                <td class={classes!(row.indent.then_some("indent"))}>{&row.source}</td>
+                                <td>{format!("{:>-.6}", row.stddev)}</td>
+                                if with_ci {
+                                    <td>{ci(row.intervals.map(|i| i.varcomp.sqrt()))}</td>
+                                }
+                                <td>{format!("{:>-.6}", row.stddev * props.study_var)}</td>
+                                <td>{format!("{:>-.2}%", row.stddev / stddev_total * 100.0)}</td>
+                                if with_ci {
+                                    <td>{percent_ci(row.intervals.and_then(|i| i.contribution).map(Interval::sqrt))}</td>
+                                }
+                                if let Some(historical) = props.historical_stddev {
+                                    <td>{format!("{:>-.2}%", process_stddev(row) / historical * 100.0)}</td>
+                                }
+                                if let Some(tolerance) = props.tolerance {
+                                    <td>{format!("{:>-.2}%", row.stddev * props.study_var / tolerance * 100.0)}</td>
+                                }
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+                { ndc_note(props.ndc, props.ndc_interval) }
+                </div>
+            </>
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use gagerr_core::linearity_study::ReferenceBias;
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct LinearityTableProps {
+    pub slope: f64,
+    pub intercept: f64,
+    pub se_slope: f64,
+    pub se_intercept: f64,
+    pub p_slope: f64,
+    pub p_intercept: f64,
+    pub s: f64,
+    pub r_squared: f64,
+    pub linearity: Option<f64>,
+    pub percent_linearity: f64,
+    pub bias: Vec<ReferenceBias>,
+    pub average_bias: Option<ReferenceBias>,
+}
+
+pub struct LinearityTable;
+
+impl Component for LinearityTable {
+    type Message = ();
+    type Properties = LinearityTableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let bias_row = |b: &ReferenceBias| {
+            html! {
+                <tr>
+                    <td>{b.reference.map(|r| format!("{:>-.6}", r)).unwrap_or_else(|| "Average".to_owned())}</td>
+                    <td>{b.n}</td>
+                    <td>{format!("{:>-.6}", b.bias)}</td>
+                    <td>{b.percent_bias.map(|p| format!("{:>-.2}%", p)).unwrap_or_default()}</td>
+                    <td>{format!("{:>-.6}", b.p)}</td>
+                </tr>
+            }
+        };
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Gage Linearity"}</h2></div>
+                <table class="anova-table">
+                    <thead>
+                        <tr>
+                            <th>{"Predictor"}</th>
+                            <th>{"Coef"}</th>
+                            <th>{"SE Coef"}</th>
+                            <th>{"P"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td>{"Constant"}</td>
+                            <td>{format!("{:>-.6}", props.intercept)}</td>
+                            <td>{format!("{:>-.6}", props.se_intercept)}</td>
+                            <td>{format!("{:>-.6}", props.p_intercept)}</td>
+                        </tr>
+                        <tr>
+                            <td>{"Slope"}</td>
+                            <td>{format!("{:>-.6}", props.slope)}</td>
+                            <td>{format!("{:>-.6}", props.se_slope)}</td>
+                            <td>{format!("{:>-.6}", props.p_slope)}</td>
+                        </tr>
+                    </tbody>
+                </table>
+                <p class="table-note">
+                    {format!("S = {:.6}, R-Sq = {:.2}%", props.s, props.r_squared * 100.0)}
+                </p>
+                <p class="table-note">
+                    {match props.linearity {
+                        Some(l) => format!("Linearity = {:.6}, % Linearity = {:.2}%", l, props.percent_linearity),
+                        None => format!("% Linearity = {:.2}%", props.percent_linearity),
+                    }}
+                </p>
+                </div>
+                <div class="data">
+                <div class="table-header"><h2>{"Gage Bias"}</h2></div>
+                <table class="anova-table">
+                    <thead>
+                        <tr>
+                            <th>{"Reference"}</th>
+                            <th>{"N"}</th>
+                            <th>{"Bias"}</th>
+                            <th>{"% Bias"}</th>
+                            <th>{"P"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for props.average_bias.iter().map(bias_row) }
+                        { for props.bias.iter().map(bias_row) }
+                    </tbody>
+                </table>
+                </div>
+            </>
+        }
+    }
+}
@@ -1,9 +1,17 @@
 use crate::models::gage_data::GageData;
+use std::rc::Rc;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct ListProps {
     pub message: GageData,
+    /// Extra columns, shared by all rows
+    pub columns: Rc<Vec<String>>,
+    pub show_reference: bool,
+    pub show_rating: bool,
+    /// Whether the row belongs to the part and operator picked on a chart
+    #[prop_or_default]
+    pub selected: bool,
 }
 
 pub struct ListItem {
@@ -28,11 +36,23 @@ impl Component for ListItem {
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
-            <tr>
+            <tr id={format!("row-{}", ctx.props().message.row)} class={classes!(ctx.props().selected.then_some("selected"))}>
                 <td class="list-item-part">{&ctx.props().message.part}</td>
                 <td class="list-item-operator">{&ctx.props().message.operator}</td>
                 <td class="list-item-trial">{&ctx.props().message.trial}</td>
                 <td class="list-item-value">{format!("{:>-.6}",&ctx.props().message.value)}</td>
+                if ctx.props().show_reference {
+                    <td class="list-item-reference">
+                        {ctx.props().message.reference.map(|r| format!("{:>-.6}", r)).unwrap_or_default()}
+                    </td>
+                }
+                if ctx.props().show_rating {
+                    <td class="list-item-rating">{ctx.props().message.rating.clone().unwrap_or_default()}</td>
+                    <td class="list-item-standard">{ctx.props().message.standard.clone().unwrap_or_default()}</td>
+                }
+                { for ctx.props().columns.iter().map(|c| html! {
+                    <td class="list-item-factor">{ctx.props().message.factors.get(c).cloned().unwrap_or_default()}</td>
+                })}
             </tr>
         }
     }
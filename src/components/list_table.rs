@@ -1,22 +1,79 @@
+use crate::components::agreement_table::AgreementTable;
 use crate::components::anova_table::AnovaTable;
+use crate::components::data_quality_panel::DataQualityPanel;
+use crate::components::expanded_anova_table::ExpandedAnovaTable;
+use crate::components::expanded_varcomp_table::ExpandedVarCompTable;
+use crate::components::linearity_table::LinearityTable;
 use crate::components::list_item::ListItem;
+use crate::components::measurement_charts::MeasurementCharts;
+use crate::components::nested_anova_table::NestedAnovaTable;
+use crate::components::range_method_table::RangeMethodTable;
+use crate::components::run_chart::RunChart;
 use crate::components::study_var_table::StudyVarTable;
+use crate::components::type1_table::Type1Table;
+use crate::components::varcomp_chart::VarCompChart;
 use crate::components::varcomp_table::VarCompTable;
+use crate::components::xbar_r_chart::XbarRChart;
+use crate::models::data_quality::{DataQualityReport, RejectedRow};
 use crate::models::gage_data::GageData;
-use crate::models::gage_study_data::GageStudyData;
-use crate::models::gage_study_data_set::GageStudyDataSet;
-use csv::Reader;
+use crate::models::import_mapping::{ImportField, ImportLayout, ImportMapping, ImportPreview};
+use csv::{Reader, StringRecord};
+use gagerr_core::attribute_agreement::{
+    Agreement, AppraiserAgreement, AttributeAgreement, KappaRow, KendallRow,
+};
+use gagerr_core::expanded_gage_study_data_set::{
+    ExpandedGageStudyDataSet, ExtraFactor, FactorRole,
+};
+use gagerr_core::gage_error::GageError;
+use gagerr_core::gage_study_data::GageStudyData;
+use gagerr_core::gage_study_data_set::GageStudyDataSet;
+use gagerr_core::linearity_study::LinearityStudy;
+use gagerr_core::nested_gage_study_data_set::NestedGageStudyDataSet;
+use gagerr_core::spec_limits::SpecLimits;
+use gagerr_core::study_type::{AnalysisMethod, StudyType, VarCompMethod};
+use gagerr_core::type1_gage_study::{Type1GageStudy, Type1Settings};
 use gloo_file::{callbacks::FileReader, File};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::rc::Rc;
 use wasm_bindgen::JsCast;
-use web_sys::{EventTarget, HtmlInputElement};
+use web_sys::{EventTarget, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 
+/// Measurements shown before a mapped file is imported
+const IMPORT_PREVIEW_ROWS: usize = 20;
+/// Measurements shown on a page of the data table, so that large files do
+/// not render a row per measurement
+const DATA_PAGE_ROWS: usize = 100;
+
 #[derive(Debug)]
 pub enum Msg {
     File(Option<File>),
     Loaded(String, String),
     ClearFile,
+    SetStudyType(StudyType),
+    SetMethod(AnalysisMethod),
+    SetVarCompMethod(VarCompMethod),
+    SetPoolInteraction(bool),
+    SetAlphaToRemove(f64),
+    SetFactorRole(String, Option<FactorRole>),
+    SetType1Settings(Type1Settings),
+    SetPartReference(String, Option<f64>),
+    SetProcessVariation(Option<f64>),
+    SetOrdinal(bool),
+    SetSpecLimits(SpecLimits),
+    SetHistoricalStdDev(Option<f64>),
+    SetConfidence(f64),
+    SelectCell(String, u32),
+    SetDataPage(usize),
+    EditRejected(u64, usize, String),
+    RetryRejected(u64),
+    SkipRejected(u64),
+    SkipAllRejected,
+    AbortImport,
+    SetImportLayout(ImportLayout),
+    MapImportColumn(ImportField, Option<usize>),
+    SetWideColumn(usize, Option<(u32, u32)>),
+    ApplyImport,
 }
 
 #[derive(Properties, PartialEq)]
@@ -25,10 +82,47 @@ pub struct ListTableProps {
 }
 
 pub struct ListTable {
-    pub items: Vec<GageData>,
+    /// Shared with the charts, so that rendering them does not copy the data
+    pub items: Rc<Vec<GageData>>,
+    /// Page of the data table on display, from 0
+    pub data_page: usize,
     pub raw_data: String,
+    pub headers: StringRecord,
+    pub extra_columns: Vec<String>,
+    /// Lines of the file that could not be read and were not skipped yet
+    pub rejected: Vec<RejectedRow>,
+    /// Number of rejected lines the user chose to skip
+    pub skipped: usize,
     pub readers: HashMap<String, FileReader>,
+    pub study_type: StudyType,
+    pub method: AnalysisMethod,
+    pub varcomp_method: VarCompMethod,
+    /// Whether a non-significant part/operator interaction is pooled into
+    /// repeatability, and the p-value above which it is
+    pub pool_interaction: bool,
+    pub alpha_to_remove: f64,
+    pub spec_limits: SpecLimits,
+    pub historical_stddev: Option<f64>,
+    pub confidence: f64,
     pub anova_data: GageStudyDataSet,
+    pub nested_data: NestedGageStudyDataSet,
+    pub extra_factors: Vec<ExtraFactor>,
+    pub expanded_data: ExpandedGageStudyDataSet,
+    pub type1_data: Type1GageStudy,
+    pub part_references: BTreeMap<String, f64>,
+    pub linearity_data: LinearityStudy,
+    pub attribute_data: AttributeAgreement,
+    /// Why the current study could not be evaluated
+    pub error: Option<GageError>,
+    /// Layout problems found in the loaded file
+    pub quality: DataQualityReport,
+    /// Part and operator picked on a control chart
+    pub selected: Option<(String, u32)>,
+    /// Column mapping of a file without the standard headers, until it is
+    /// applied
+    pub import: Option<ImportMapping>,
+    /// Measurements read with `import`
+    pub import_preview: ImportPreview,
 }
 
 impl Component for ListTable {
@@ -37,30 +131,76 @@ impl Component for ListTable {
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
-            items: Vec::new(),
+            items: Rc::default(),
+            data_page: 0,
             raw_data: String::new(),
+            headers: StringRecord::new(),
+            extra_columns: Vec::new(),
+            rejected: Vec::new(),
+            skipped: 0,
             readers: HashMap::new(),
+            study_type: StudyType::default(),
+            method: AnalysisMethod::default(),
+            varcomp_method: VarCompMethod::default(),
+            pool_interaction: true,
+            alpha_to_remove: 0.25,
+            spec_limits: SpecLimits::default(),
+            historical_stddev: None,
+            confidence: 0.95,
             anova_data: GageStudyDataSet::new(),
+            nested_data: NestedGageStudyDataSet::new(),
+            extra_factors: Vec::new(),
+            expanded_data: ExpandedGageStudyDataSet::new(),
+            type1_data: Type1GageStudy::new(),
+            part_references: BTreeMap::new(),
+            linearity_data: LinearityStudy::new(),
+            attribute_data: AttributeAgreement::new(),
+            error: None,
+            quality: DataQualityReport::default(),
+            selected: None,
+            import: None,
+            import_preview: ImportPreview::default(),
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::Loaded(fname, data) => {
-                self.items = Vec::new();
+                self.items = Rc::default();
+                self.data_page = 0;
                 self.raw_data = data;
                 log::info!("File loaded...");
                 self.readers.remove(&fname);
                 let mut rdr = Reader::from_reader(self.raw_data.as_bytes());
-                let iter = rdr.deserialize();
-                for result in iter {
-                    match result {
-                        Ok(r) => self.items.push(r),
-                        Err(e) => log::info!("{e}"),
+                self.headers = rdr.headers().cloned().unwrap_or_default();
+                self.extra_columns = GageData::extra_columns(&self.headers);
+                self.extra_factors
+                    .retain(|f| self.extra_columns.contains(&f.name));
+                self.rejected = Vec::new();
+                self.skipped = 0;
+                self.selected = None;
+                self.import = None;
+                if !ImportMapping::is_standard(&self.headers) {
+                    // Nothing is analysed until the columns are mapped
+                    self.import = Some(ImportMapping::detect(&self.headers));
+                    self.read_import();
+                    self.clear_results();
+                    return true;
+                }
+                for result in rdr.records() {
+                    let parsed = match result {
+                        Ok(record) => GageData::from_record(&record, &self.headers)
+                            .map_err(|e| RejectedRow::new(&e, &self.headers, Some(&record))),
+                        Err(e) => Err(RejectedRow::new(&e, &self.headers, None)),
+                    };
+                    match parsed {
+                        Ok(r) => Rc::make_mut(&mut self.items).push(r),
+                        Err(rejected) => self.rejected.push(rejected),
                     }
                 }
+                self.quality = DataQualityReport::new(&self.items);
                 log::info!("Items loaded: {}", self.items.len());
-                self.anova_data = self.process_gage_study();
+                self.process();
                 true
             }
             Msg::File(f) => {
@@ -78,6 +218,178 @@ impl Component for ListTable {
                 self.raw_data = String::new();
                 false
             }
+            Msg::SetStudyType(study_type) => {
+                self.study_type = study_type;
+                self.process();
+                true
+            }
+            Msg::SetType1Settings(settings) => {
+                self.type1_data.settings = settings;
+                self.process();
+                true
+            }
+            Msg::SetPartReference(part, reference) => {
+                match reference {
+                    Some(r) => self.part_references.insert(part, r),
+                    None => self.part_references.remove(&part),
+                };
+                self.process();
+                true
+            }
+            Msg::SetProcessVariation(pv) => {
+                self.linearity_data.process_variation = pv;
+                self.process();
+                true
+            }
+            Msg::SetSpecLimits(spec_limits) => {
+                self.spec_limits = spec_limits;
+                self.process();
+                true
+            }
+            Msg::SetHistoricalStdDev(historical_stddev) => {
+                self.historical_stddev = historical_stddev.filter(|h| *h > 0.0);
+                self.process();
+                true
+            }
+            Msg::SetConfidence(confidence) => {
+                self.confidence = confidence;
+                self.process();
+                true
+            }
+            Msg::SelectCell(part, operator) => {
+                // Show the first measurement of the cell in the data table
+                if let Some(index) = self
+                    .items
+                    .iter()
+                    .position(|i| i.part == part && i.operator == operator)
+                {
+                    self.data_page = index / DATA_PAGE_ROWS;
+                }
+                self.selected = Some((part, operator));
+                true
+            }
+            Msg::SetDataPage(page) => {
+                self.data_page = page;
+                true
+            }
+            Msg::SetOrdinal(ordinal) => {
+                self.attribute_data.ordinal = ordinal;
+                self.process();
+                true
+            }
+            Msg::EditRejected(row, field, value) => {
+                let rejected = self.rejected.iter_mut().find(|r| r.row == row);
+                if let Some(f) = rejected.and_then(|r| r.fields.get_mut(field)) {
+                    *f = value;
+                }
+                false
+            }
+            Msg::RetryRejected(row) => {
+                let position = self.rejected.iter().position(|r| r.row == row);
+                let fixed = position.and_then(|p| self.rejected[p].retry(&self.headers));
+                if let (Some(p), Some(data)) = (position, fixed) {
+                    self.rejected.remove(p);
+                    // Keep the measurements in file order
+                    let index = self.items.partition_point(|i| i.row < data.row);
+                    Rc::make_mut(&mut self.items).insert(index, data);
+                    self.quality = DataQualityReport::new(&self.items);
+                    self.process();
+                }
+                true
+            }
+            Msg::SkipRejected(row) => {
+                self.rejected.retain(|r| r.row != row);
+                self.skipped += 1;
+                true
+            }
+            Msg::SkipAllRejected => {
+                self.skipped += self.rejected.len();
+                self.rejected.clear();
+                true
+            }
+            Msg::AbortImport => {
+                self.items = Rc::default();
+                self.raw_data.clear();
+                self.extra_columns.clear();
+                self.rejected.clear();
+                self.skipped = 0;
+                self.import = None;
+                self.import_preview = ImportPreview::default();
+                self.clear_results();
+                true
+            }
+            Msg::SetImportLayout(layout) => {
+                if let Some(import) = self.import.as_mut() {
+                    import.layout = layout;
+                }
+                self.read_import();
+                true
+            }
+            Msg::MapImportColumn(field, column) => {
+                if let Some(import) = self.import.as_mut() {
+                    import.set_column(field, column);
+                }
+                self.read_import();
+                true
+            }
+            Msg::SetWideColumn(column, operator_trial) => {
+                if let Some(import) = self.import.as_mut() {
+                    import.set_measurement(column, operator_trial);
+                }
+                self.read_import();
+                true
+            }
+            Msg::ApplyImport => {
+                let preview = std::mem::take(&mut self.import_preview);
+                self.import = None;
+                self.items = Rc::new(preview.items);
+                self.data_page = 0;
+                self.extra_columns = preview.extra_columns;
+                self.extra_factors
+                    .retain(|f| self.extra_columns.contains(&f.name));
+                self.quality = DataQualityReport::new(&self.items);
+                log::info!("Items imported: {}", self.items.len());
+                self.process();
+                true
+            }
+            Msg::SetMethod(method) => {
+                self.method = method;
+                true
+            }
+            Msg::SetVarCompMethod(method) => {
+                self.varcomp_method = method;
+                self.process();
+                true
+            }
+            Msg::SetPoolInteraction(pool) => {
+                self.pool_interaction = pool;
+                self.process();
+                true
+            }
+            Msg::SetAlphaToRemove(alpha) => {
+                self.alpha_to_remove = alpha;
+                self.process();
+                true
+            }
+            Msg::SetFactorRole(name, role) => {
+                match role {
+                    Some(role) => match self.extra_factors.iter_mut().find(|f| f.name == name) {
+                        Some(factor) => factor.role = role,
+                        None => self.extra_factors.push(ExtraFactor::new(&name, role)),
+                    },
+                    None => {
+                        self.extra_factors.retain(|f| f.name != name);
+                        // Nothing can stay nested in a factor that was removed
+                        for factor in self.extra_factors.iter_mut() {
+                            if factor.role == FactorRole::NestedIn(name.clone()) {
+                                factor.role = FactorRole::Crossed;
+                            }
+                        }
+                    }
+                }
+                self.process();
+                true
+            }
         }
     }
 
@@ -93,11 +405,98 @@ impl Component for ListTable {
                 _ => Msg::ClearFile,
             }
         });
+        let on_study_type = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            select.and_then(|s| s.value().parse().ok().map(Msg::SetStudyType))
+        });
+        let on_method = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            select.and_then(|s| s.value().parse().ok().map(Msg::SetMethod))
+        });
+        let on_varcomp_method = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            select.and_then(|s| s.value().parse().ok().map(Msg::SetVarCompMethod))
+        });
+        let on_pool = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|i| Msg::SetPoolInteraction(i.checked()))
+        });
+        let on_alpha = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input
+                .and_then(|i| i.value().parse::<f64>().ok())
+                .filter(|a| (0.0..=1.0).contains(a))
+                .map(Msg::SetAlphaToRemove)
+        });
+        // Shared by every row of the data table
+        let has_reference = self.has_reference();
+        let has_rating = self.has_rating();
+        let columns = Rc::new(self.extra_columns.clone());
         html! {
             <>
             <div class="container">
                 <input type="file" id="csvinput" name="csvinput" accept=".csv" onchange={dummy}/>
+                if !self.raw_data.is_empty() && self.import.is_none() {
+                    <span class="import-count">
+                        {format!(
+                            "{} rows accepted, {} rejected",
+                            self.items.len(),
+                            self.rejected.len() + self.skipped,
+                        )}
+                    </span>
+                }
+            </div>
+            if let Some(import) = &self.import {
+                { self.view_import(ctx, import) }
+            }
+            if !self.rejected.is_empty() {
+                { self.view_rejected_rows(ctx) }
+            }
+            <div class="options">
+                <label for="studytype">{"Study type "}</label>
+                <select id="studytype" name="studytype" onchange={on_study_type}>
+                    { for StudyType::ALL.iter().map(|t| html! {
+                        <option value={t.to_string()} selected={*t == self.study_type}>{t.to_string()}</option>
+                    })}
+                </select>
+                if self.study_type == StudyType::Crossed {
+                    <label for="method">{" Method "}</label>
+                    <select id="method" name="method" onchange={on_method}>
+                        { for AnalysisMethod::ALL.iter().map(|m| html! {
+                            <option value={m.to_string()} selected={*m == self.method}>{m.to_string()}</option>
+                        })}
+                    </select>
+                    if self.method != AnalysisMethod::Range {
+                        <label for="varcompmethod">{" Variance components "}</label>
+                        <select id="varcompmethod" name="varcompmethod" onchange={on_varcomp_method}>
+                            { for VarCompMethod::ALL.iter().map(|m| html! {
+                                <option value={m.to_string()} selected={*m == self.varcomp_method}>{m.to_string()}</option>
+                            })}
+                        </select>
+                        <label>
+                            <input type="checkbox" checked={self.pool_interaction} onchange={on_pool} />
+                            {" Pool interaction, α to remove "}
+                            <input
+                                type="number"
+                                step="0.01"
+                                min="0"
+                                max="1"
+                                value={self.alpha_to_remove.to_string()}
+                                disabled={!self.pool_interaction}
+                                onchange={on_alpha}
+                            />
+                        </label>
+                    }
+                }
             </div>
+            if matches!(self.study_type, StudyType::Crossed | StudyType::Nested | StudyType::Expanded) {
+                { self.view_spec_limits(ctx) }
+            }
             <div class="data">
                 <table class="list-table">
                     <tr>
@@ -105,15 +504,452 @@ impl Component for ListTable {
                         <th>{"Operator"}</th>
                         <th>{"Trial"}</th>
                         <th>{"Value"}</th>
+                        if has_reference {
+                            <th>{"Reference"}</th>
+                        }
+                        if has_rating {
+                            <th>{"Rating"}</th>
+                            <th>{"Standard"}</th>
+                        }
+                        { for self.extra_columns.iter().map(|c| html! { <th>{c}</th> }) }
                     </tr>
                     { for self.items
                         .iter()
+                        .skip(self.data_page * DATA_PAGE_ROWS)
+                        .take(DATA_PAGE_ROWS)
                         .map(|col| html_nested!{
-                            <ListItem message={col.clone()} />
+                            <ListItem
+                                message={col.clone()}
+                                columns={columns.clone()}
+                                show_reference={has_reference}
+                                show_rating={has_rating}
+                                selected={self.selected.as_ref().is_some_and(|(p, o)| *p == col.part && *o == col.operator)}
+                            />
                         })
                     }
                 </table>
+                { self.view_data_pager(ctx) }
+            </div>
+            if matches!(self.study_type, StudyType::Crossed | StudyType::Expanded) {
+                <DataQualityPanel report={self.quality.clone()} />
+            }
+            if let Some(error) = &self.error {
+                <div class="data">
+                    <p class="table-note warning">{error.to_string()}</p>
+                </div>
+            } else {
+                {
+                    match self.study_type {
+                        StudyType::Crossed => match self.method {
+                            AnalysisMethod::Anova => self.view_crossed(),
+                            AnalysisMethod::Range => self.view_range_method(),
+                            AnalysisMethod::Both => html! {
+                                <div class="side-by-side">
+                                    <div>{ self.view_crossed() }</div>
+                                    <div>{ self.view_range_method() }</div>
+                                </div>
+                            },
+                        },
+                        StudyType::Nested => self.view_nested(),
+                        StudyType::Expanded => self.view_expanded(ctx),
+                        StudyType::Type1 => self.view_type1(ctx),
+                        StudyType::Linearity => self.view_linearity(ctx),
+                        StudyType::Attribute => self.view_attribute(ctx),
+                    }
+                }
+            }
+            if self.study_type == StudyType::Crossed && !self.items.is_empty() {
+                { self.view_charts(ctx) }
+            }
+            </>
+        }
+    }
+}
+
+impl ListTable {
+    /// Controls to page through the data table, when it has more than one
+    /// page.
+    fn view_data_pager(&self, ctx: &Context<Self>) -> Html {
+        let pages = self.items.len().div_ceil(DATA_PAGE_ROWS);
+        if pages <= 1 {
+            return html! {};
+        }
+        let page = self.data_page.min(pages - 1);
+        let first = page * DATA_PAGE_ROWS + 1;
+        let last = ((page + 1) * DATA_PAGE_ROWS).min(self.items.len());
+        html! {
+            <p class="table-note">
+                <button
+                    disabled={page == 0}
+                    onclick={ctx.link().callback(|_| Msg::SetDataPage(0))}
+                >{"First"}</button>
+                <button
+                    disabled={page == 0}
+                    onclick={ctx.link().callback(move |_| Msg::SetDataPage(page.saturating_sub(1)))}
+                >{"Previous"}</button>
+                {format!(" Rows {}–{} of {} ", first, last, self.items.len())}
+                <button
+                    disabled={page + 1 == pages}
+                    onclick={ctx.link().callback(move |_| Msg::SetDataPage(page + 1))}
+                >{"Next"}</button>
+                <button
+                    disabled={page + 1 == pages}
+                    onclick={ctx.link().callback(move |_| Msg::SetDataPage(pages - 1))}
+                >{"Last"}</button>
+            </p>
+        }
+    }
+
+    pub fn process(&mut self) {
+        if self.items.is_empty() {
+            self.clear_results();
+            self.error = Some(GageError::EmptyData);
+            return;
+        }
+        let result = match self.study_type {
+            StudyType::Crossed => self.process_gage_study().map(|d| self.anova_data = d),
+            StudyType::Nested => self
+                .process_nested_gage_study()
+                .map(|d| self.nested_data = d),
+            StudyType::Expanded => self
+                .process_expanded_gage_study()
+                .map(|d| self.expanded_data = d),
+            StudyType::Type1 => {
+                self.type1_data = self.process_type1_gage_study();
+                Ok(())
+            }
+            StudyType::Linearity => {
+                self.linearity_data = self.process_linearity_study();
+                Ok(())
+            }
+            StudyType::Attribute => {
+                self.attribute_data = self.process_attribute_agreement();
+                Ok(())
+            }
+        };
+        self.error = result.err();
+    }
+
+    /// Drops the results of the previous data, keeping the settings that are
+    /// stored with them.
+    fn clear_results(&mut self) {
+        self.quality = DataQualityReport::default();
+        self.error = None;
+        self.anova_data = GageStudyDataSet::new();
+        self.nested_data = NestedGageStudyDataSet::new();
+        self.expanded_data = ExpandedGageStudyDataSet::new();
+        let mut type1 = Type1GageStudy::new();
+        type1.settings = self.type1_data.settings;
+        self.type1_data = type1;
+        let mut linearity = LinearityStudy::new();
+        linearity.process_variation = self.linearity_data.process_variation;
+        self.linearity_data = linearity;
+        let mut attribute = AttributeAgreement::new();
+        attribute.ordinal = self.attribute_data.ordinal;
+        self.attribute_data = attribute;
+    }
+
+    fn read_import(&mut self) {
+        self.import_preview = self
+            .import
+            .as_ref()
+            .map(|import| import.read(&self.raw_data))
+            .unwrap_or_default();
+    }
+
+    /// Mapping of the columns of a file without the standard headers to
+    /// measurements, and the measurements it gives.
+    fn view_import(&self, ctx: &Context<Self>, import: &ImportMapping) -> Html {
+        let on_layout = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            select.and_then(|s| s.value().parse().ok().map(Msg::SetImportLayout))
+        });
+        let column_select = |field: ImportField, none: &'static str| {
+            let onchange = ctx.link().batch_callback(move |e: Event| {
+                let target: Option<EventTarget> = e.target();
+                let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+                select.map(|s| Msg::MapImportColumn(field, s.value().parse().ok()))
+            });
+            let selected = import.column(field);
+            html! {
+                <>
+                    <label>{format!(" {} ", field)}</label>
+                    <select {onchange}>
+                        <option value="" selected={selected.is_none()}>{none}</option>
+                        { for import.headers.iter().enumerate().map(|(c, h)| html! {
+                            <option value={c.to_string()} selected={selected == Some(c)}>{h}</option>
+                        })}
+                    </select>
+                </>
+            }
+        };
+        let wide_column = |(column, header): (usize, &String)| {
+            let measurement = import.measurements.iter().find(|m| m.column == column);
+            let operator_trial = measurement.map(|m| (m.operator, m.trial));
+            let guess = ImportMapping::parse_column_name(header).unwrap_or((1, 1));
+            let ontoggle = ctx.link().batch_callback(move |e: Event| {
+                let target: Option<EventTarget> = e.target();
+                let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                input.map(|i| Msg::SetWideColumn(column, i.checked().then_some(guess)))
+            });
+            let number = |set: fn((u32, u32), u32) -> (u32, u32)| {
+                let current = operator_trial.unwrap_or(guess);
+                ctx.link().batch_callback(move |e: Event| {
+                    let target: Option<EventTarget> = e.target();
+                    let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                    let value = input.and_then(|i| i.value().parse().ok());
+                    value.map(|v| Msg::SetWideColumn(column, Some(set(current, v))))
+                })
+            };
+            html! {
+                <tr>
+                    <td>{header}</td>
+                    <td><input type="checkbox" checked={measurement.is_some()} onchange={ontoggle} /></td>
+                    if let Some((operator, trial)) = operator_trial {
+                        <td><input type="number" class="field" min="1" value={operator.to_string()}
+                            onchange={number(|(_, t), o| (o, t))} /></td>
+                        <td><input type="number" class="field" min="1" value={trial.to_string()}
+                            onchange={number(|(o, _), t| (o, t))} /></td>
+                    } else {
+                        <td></td>
+                        <td></td>
+                    }
+                </tr>
+            }
+        };
+        let preview = &self.import_preview;
+        html! {
+            <div class="data">
+                <div class="table-header"><h2>{"Import"}</h2></div>
+                <p class="table-note">
+                    {"The file does not have Part, Operator, Trial and Value columns. Choose where \
+                      they are, check the measurements below and import them."}
+                </p>
+                <div class="options">
+                    <label for="importlayout">{"Layout "}</label>
+                    <select id="importlayout" name="importlayout" onchange={on_layout}>
+                        { for ImportLayout::ALL.iter().map(|l| html! {
+                            <option value={l.to_string()} selected={*l == import.layout}>{l.to_string()}</option>
+                        })}
+                    </select>
+                    { column_select(ImportField::Part, "None") }
+                    if import.layout == ImportLayout::Long {
+                        { column_select(ImportField::Operator, "None") }
+                        { column_select(ImportField::Trial, "Number in order") }
+                        { column_select(ImportField::Value, "None") }
+                    }
+                </div>
+                if import.layout == ImportLayout::Wide {
+                    <table class="anova-table">
+                        <thead>
+                            <tr>
+                                <th>{"Column"}</th>
+                                <th>{"Measurement"}</th>
+                                <th>{"Operator"}</th>
+                                <th>{"Trial"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for import.headers.iter().enumerate().filter(|(c, _)| import.part != Some(*c)).map(wide_column) }
+                        </tbody>
+                    </table>
+                }
+                if let Some(missing) = import.missing() {
+                    <p class="table-note warning">{missing}</p>
+                } else {
+                    <p class="table-note">
+                        {format!(
+                            "{} measurements of {} parts. The first {} are shown.",
+                            preview.items.len(),
+                            preview.items.iter().map(|i| &i.part).collect::<BTreeSet<_>>().len(),
+                            preview.items.len().min(IMPORT_PREVIEW_ROWS),
+                        )}
+                    </p>
+                    { for preview.notes.iter().map(|note| html! {
+                        <p class="table-note warning">{note}</p>
+                    })}
+                    <table class="anova-table">
+                        <thead>
+                            <tr>
+                                <th>{"Part"}</th>
+                                <th>{"Operator"}</th>
+                                <th>{"Trial"}</th>
+                                <th>{"Value"}</th>
+                                { for preview.extra_columns.iter().map(|c| html! { <th>{c}</th> }) }
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for preview.items.iter().take(IMPORT_PREVIEW_ROWS).map(|i| html! {
+                                <tr>
+                                    <td>{&i.part}</td>
+                                    <td>{i.operator}</td>
+                                    <td>{i.trial}</td>
+                                    <td>{i.value}</td>
+                                    { for preview.extra_columns.iter().map(|c| html! {
+                                        <td>{i.factors.get(c).cloned().unwrap_or_default()}</td>
+                                    })}
+                                </tr>
+                            })}
+                        </tbody>
+                    </table>
+                }
+                <div class="options">
+                    <button disabled={preview.items.is_empty()} onclick={ctx.link().callback(|_| Msg::ApplyImport)}>
+                        {"Import"}
+                    </button>
+                    <button onclick={ctx.link().callback(|_| Msg::AbortImport)}>{"Cancel"}</button>
+                </div>
             </div>
+        }
+    }
+
+    // The prop checks html! generates move the props out, which clippy
+    // flags for props with non-trivial drops such as callbacks
+    #[allow(clippy::unnecessary_operation)]
+    fn view_charts(&self, ctx: &Context<Self>) -> Html {
+        let onselect = ctx
+            .link()
+            .callback(|(part, operator)| Msg::SelectCell(part, operator));
+        html! {
+            <>
+                <XbarRChart items={self.items.clone()} {onselect} />
+                <MeasurementCharts items={self.items.clone()} />
+            </>
+        }
+    }
+
+    /// Lines of the file that could not be read, each with its fields for
+    /// fixing it in place.
+    fn view_rejected_rows(&self, ctx: &Context<Self>) -> Html {
+        let row = |rejected: &RejectedRow| {
+            let line = rejected.row;
+            let fields = rejected.fields.iter().enumerate().map(|(field, value)| {
+                let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+                    let target: Option<EventTarget> = e.target();
+                    let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                    input.map(|i| Msg::EditRejected(line, field, i.value()))
+                });
+                let header = self.headers.get(field).unwrap_or_default();
+                let invalid = rejected.column.as_deref() == Some(header);
+                html! {
+                    <input
+                        type="text"
+                        class={classes!("field", invalid.then_some("invalid"))}
+                        title={header.to_owned()}
+                        value={value.clone()}
+                        {oninput}
+                    />
+                }
+            });
+            html! {
+                <tr>
+                    <td>{line}</td>
+                    <td>{rejected.column.clone().unwrap_or_default()}</td>
+                    <td>{&rejected.reason}</td>
+                    <td>{ for fields }</td>
+                    <td>
+                        if !rejected.fields.is_empty() {
+                            <button onclick={ctx.link().callback(move |_| Msg::RetryRejected(line))}>{"Fix"}</button>
+                        }
+                        <button onclick={ctx.link().callback(move |_| Msg::SkipRejected(line))}>{"Skip"}</button>
+                    </td>
+                </tr>
+            }
+        };
+        html! {
+            <div class="data">
+                <div class="table-header"><h2>{"Rejected Rows"}</h2></div>
+                <p class="table-note warning">
+                    {"These lines were left out of the results. Edit and fix them, skip them, or abort the import."}
+                </p>
+                <table class="anova-table">
+                    <thead>
+                        <tr>
+                            <th>{"Line"}</th>
+                            <th>{"Column"}</th>
+                            <th>{"Reason"}</th>
+                            <th>{"Fields"}</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for self.rejected.iter().map(row) }
+                    </tbody>
+                </table>
+                <div class="options">
+                    <button onclick={ctx.link().callback(|_| Msg::SkipAllRejected)}>{"Skip all"}</button>
+                    <button onclick={ctx.link().callback(|_| Msg::AbortImport)}>{"Abort import"}</button>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_spec_limits(&self, ctx: &Context<Self>) -> Html {
+        let spec_limits = self.spec_limits;
+        let on_historical_stddev = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|i| Msg::SetHistoricalStdDev(i.value().parse::<f64>().ok()))
+        });
+        let on_confidence = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            select.and_then(|s| s.value().parse().ok().map(Msg::SetConfidence))
+        });
+        let input = |label: &str, value: Option<f64>, update: fn(&mut SpecLimits, Option<f64>)| {
+            let onchange = ctx.link().batch_callback(move |e: Event| {
+                let target: Option<EventTarget> = e.target();
+                let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                input.map(|i| {
+                    let mut spec_limits = spec_limits;
+                    update(&mut spec_limits, i.value().parse::<f64>().ok());
+                    Msg::SetSpecLimits(spec_limits)
+                })
+            });
+            html! {
+                <label>
+                    {format!("{} ", label)}
+                    <input
+                        type="number"
+                        step="any"
+                        value={value.map(|v| v.to_string()).unwrap_or_default()}
+                        {onchange}
+                    />
+                </label>
+            }
+        };
+        html! {
+            <div class="options">
+                { input("LSL", spec_limits.lsl, |s, v| s.lsl = v) }
+                { input("USL", spec_limits.usl, |s, v| s.usl = v) }
+                { input("Tolerance", spec_limits.width, |s, v| s.width = v) }
+                <label>
+                    {"Historical StdDev "}
+                    <input
+                        type="number"
+                        step="any"
+                        value={self.historical_stddev.map(|v| v.to_string()).unwrap_or_default()}
+                        onchange={on_historical_stddev}
+                    />
+                </label>
+                <label>
+                    {"Confidence "}
+                    <select onchange={on_confidence}>
+                        { for [0.90, 0.95].iter().map(|c| html! {
+                            <option value={c.to_string()} selected={*c == self.confidence}>
+                                {format!("{:.0}%", c * 100.0)}
+                            </option>
+                        })}
+                    </select>
+                </label>
+            </div>
+        }
+    }
+
+    fn view_crossed(&self) -> Html {
+        html! {
+            <>
             <AnovaTable
                 df_part={self.anova_data.df_part}
                 df_operator={self.anova_data.df_operator}
@@ -127,44 +963,519 @@ impl Component for ListTable {
                 ss_total={self.anova_data.ss_total}
                 ms_part={self.anova_data.ms_part}
                 ms_operator={self.anova_data.ms_operator}
+                ms_part_operator={self.anova_data.ms_part_operator}
                 ms_repeatability={self.anova_data.ms_repeatability}
                 f_part={self.anova_data.f_part}
                 f_operator={self.anova_data.f_operator}
                 f_part_operator={self.anova_data.f_part_operator}
-                p={self.anova_data.p}
+                p_part={self.anova_data.p_part}
+                p_operator={self.anova_data.p_operator}
+                p_part_operator={self.anova_data.p_part_operator}
+                title={"ANOVA With Interaction"}
+                alpha_to_remove={self.anova_data.pooling_tested().then_some(self.anova_data.alpha_to_remove)}
             />
+            if let Some(reduced) = &self.anova_data.reduced_anova {
+                <AnovaTable
+                    df_part={reduced.df_part}
+                    df_operator={reduced.df_operator}
+                    df_part_operator={0}
+                    df_repeatability={reduced.df_repeatability}
+                    df_total={reduced.df_total}
+                    ss_part={reduced.ss_part}
+                    ss_operator={reduced.ss_operator}
+                    ss_part_operator={0.0}
+                    ss_repeatability={reduced.ss_repeatability}
+                    ss_total={reduced.ss_total}
+                    ms_part={reduced.ms_part}
+                    ms_operator={reduced.ms_operator}
+                    ms_part_operator={None::<f64>}
+                    ms_repeatability={reduced.ms_repeatability}
+                    f_part={reduced.f_part}
+                    f_operator={reduced.f_operator}
+                    f_part_operator={None::<f64>}
+                    p_part={reduced.p_part}
+                    p_operator={reduced.p_operator}
+                    p_part_operator={None::<f64>}
+                    title={"ANOVA Without Interaction"}
+                    alpha_to_remove={None::<f64>}
+                />
+            }
             <VarCompTable
                 varcomp_part={self.anova_data.varcomp_part}
                 varcomp_operator={self.anova_data.varcomp_operator}
-                varcomp_repeatability={self.anova_data.ms_repeatability}
+                varcomp_part_operator={self.anova_data.varcomp_part_operator}
+                varcomp_repeatability={self.anova_data.varcomp_repeatability}
+                varcomp_reproducibility={self.anova_data.varcomp_reproducibility}
+                intervals={self.anova_data.varcomp_intervals.clone()}
+                note={varcomp_method_note(&self.anova_data)}
+                truncated={self.anova_data.truncated_varcomps.clone()}
                 varcomp_total_gagerr={self.anova_data.varcomp_total_gagerr}
                 varcomp_total={self.anova_data.varcomp_total}
             />
             <StudyVarTable
-                stddev_part={self.anova_data.varcomp_part.sqrt()}
-                stddev_operator={
-                    match self.anova_data.varcomp_operator {
-                        Some(v) => Some(v.sqrt()),
-                        None => None
-                    }
-                }
-                stddev_repeatability={self.anova_data.ms_repeatability.sqrt()}
-                stddev_total_gagerr={self.anova_data.ms_repeatability.sqrt() + self.anova_data.varcomp_operator.unwrap_or(0.0).sqrt()}
-                stddev_total={self.anova_data.varcomp_total.sqrt()}
+                stddev_part={self.anova_data.stddev_part}
+                stddev_operator={self.anova_data.stddev_operator}
+                stddev_part_operator={self.anova_data.stddev_part_operator}
+                stddev_repeatability={self.anova_data.stddev_repeatability}
+                stddev_reproducibility={self.anova_data.stddev_reproducibility}
+                stddev_total_gagerr={self.anova_data.stddev_total_gagerr}
+                stddev_total={self.anova_data.stddev_total}
+                study_var={self.anova_data.study_variation}
+                ndc={self.anova_data.ndc}
+                tolerance={self.anova_data.tolerance}
+                historical_stddev={self.anova_data.historical_stddev}
+                stddev_part_process={self.anova_data.stddev_part_process}
+                intervals={self.anova_data.varcomp_intervals.clone()}
+            />
+            <VarCompChart
+                varcomp_total_gagerr={self.anova_data.varcomp_total_gagerr}
+                varcomp_repeatability={self.anova_data.varcomp_repeatability}
+                varcomp_reproducibility={self.anova_data.varcomp_reproducibility}
+                varcomp_part={self.anova_data.varcomp_part}
+                varcomp_total={self.anova_data.varcomp_total}
+                stddev_total_gagerr={self.anova_data.stddev_total_gagerr}
+                stddev_repeatability={self.anova_data.stddev_repeatability}
+                stddev_reproducibility={self.anova_data.stddev_reproducibility}
+                stddev_part={self.anova_data.stddev_part}
+                stddev_total={self.anova_data.stddev_total}
                 study_var={self.anova_data.study_variation}
+                tolerance={self.anova_data.tolerance}
             />
             </>
         }
     }
-}
 
-impl ListTable {
-    pub fn process_gage_study(&self) -> GageStudyDataSet {
-        let data = self
-            .items
+    fn view_range_method(&self) -> Html {
+        html! {
+            <RangeMethodTable range_method={self.anova_data.range_method.clone()} />
+        }
+    }
+
+    fn view_nested(&self) -> Html {
+        let nested = &self.nested_data;
+        html! {
+            <>
+            <NestedAnovaTable
+                df_operator={nested.df_operator}
+                df_part={nested.df_part}
+                df_repeatability={nested.df_repeatability}
+                df_total={nested.df_total}
+                ss_operator={nested.ss_operator}
+                ss_part={nested.ss_part}
+                ss_repeatability={nested.ss_repeatability}
+                ss_total={nested.ss_total}
+                ms_operator={nested.ms_operator}
+                ms_part={nested.ms_part}
+                ms_repeatability={nested.ms_repeatability}
+                f_operator={nested.f_operator}
+                f_part={nested.f_part}
+                p_operator={nested.p_operator}
+                p_part={nested.p_part}
+            />
+            <VarCompTable
+                varcomp_part={nested.varcomp_part}
+                varcomp_operator={nested.varcomp_operator}
+                varcomp_part_operator={None::<f64>}
+                varcomp_repeatability={nested.varcomp_repeatability}
+                varcomp_reproducibility={nested.varcomp_operator}
+                intervals={nested.varcomp_intervals.clone()}
+                truncated={nested.truncated_varcomps.clone()}
+                varcomp_total_gagerr={nested.varcomp_total_gagerr}
+                varcomp_total={nested.varcomp_total}
+            />
+            <StudyVarTable
+                stddev_part={nested.stddev_part}
+                stddev_operator={nested.stddev_operator}
+                stddev_part_operator={None::<f64>}
+                stddev_repeatability={nested.stddev_repeatability}
+                stddev_reproducibility={nested.stddev_operator}
+                stddev_total_gagerr={nested.stddev_total_gagerr}
+                stddev_total={nested.stddev_total}
+                study_var={nested.study_variation}
+                ndc={nested.ndc}
+                tolerance={nested.tolerance}
+                historical_stddev={nested.historical_stddev}
+                stddev_part_process={nested.stddev_part_process}
+                intervals={nested.varcomp_intervals.clone()}
+            />
+            <VarCompChart
+                varcomp_total_gagerr={nested.varcomp_total_gagerr}
+                varcomp_repeatability={nested.varcomp_repeatability}
+                varcomp_reproducibility={nested.varcomp_operator}
+                varcomp_part={nested.varcomp_part}
+                varcomp_total={nested.varcomp_total}
+                stddev_total_gagerr={nested.stddev_total_gagerr}
+                stddev_repeatability={nested.stddev_repeatability}
+                stddev_reproducibility={nested.stddev_operator}
+                stddev_part={nested.stddev_part}
+                stddev_total={nested.stddev_total}
+                study_var={nested.study_variation}
+                tolerance={nested.tolerance}
+            />
+            </>
+        }
+    }
+
+    fn view_expanded(&self, ctx: &Context<Self>) -> Html {
+        let factor_role = |name: &str| {
+            self.extra_factors
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| f.role.clone())
+        };
+        let view_factor = |name: &String| {
+            let column = name.clone();
+            let onchange = ctx.link().batch_callback(move |e: Event| {
+                let target: Option<EventTarget> = e.target();
+                let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+                select.map(|s| {
+                    let role = match s.value().as_str() {
+                        "ignore" => None,
+                        "crossed" => Some(FactorRole::Crossed),
+                        v => v
+                            .strip_prefix("nested:")
+                            .map(|parent| FactorRole::NestedIn(parent.to_owned())),
+                    };
+                    Msg::SetFactorRole(column.clone(), role)
+                })
+            });
+            let role = factor_role(name);
+            let parents: Vec<String> = ["Part", "Operator"]
+                .iter()
+                .map(|p| p.to_string())
+                .chain(self.extra_factors.iter().map(|f| f.name.clone()))
+                .filter(|p| p != name)
+                .collect();
+            html! {
+                <div>
+                    <label>{format!("{} ", name)}</label>
+                    <select {onchange}>
+                        <option value="ignore" selected={role.is_none()}>{"Ignore"}</option>
+                        <option value="crossed" selected={role == Some(FactorRole::Crossed)}>{"Crossed"}</option>
+                        { for parents.iter().map(|p| html! {
+                            <option
+                                value={format!("nested:{}", p)}
+                                selected={role == Some(FactorRole::NestedIn(p.clone()))}
+                            >
+                                {format!("Nested in {}", p)}
+                            </option>
+                        })}
+                    </select>
+                </div>
+            }
+        };
+        html! {
+            <>
+            <div class="options">
+                if self.extra_columns.is_empty() {
+                    <p>{"Add columns such as Fixture or Gage to the CSV to include extra factors."}</p>
+                }
+                { for self.extra_columns.iter().map(view_factor) }
+            </div>
+            <ExpandedAnovaTable rows={self.expanded_data.anova.clone()} />
+            <ExpandedVarCompTable
+                rows={self.expanded_data.varcomp_rows()}
+                varcomp_total={self.expanded_data.varcomp_total}
+                study_var={self.expanded_data.study_variation}
+                ndc={self.expanded_data.ndc}
+                tolerance={self.expanded_data.tolerance}
+                historical_stddev={self.expanded_data.historical_stddev}
+                stddev_part_process={self.expanded_data.stddev_part_process}
+                confidence={self.expanded_data.confidence}
+                ndc_interval={self.expanded_data.ndc_interval}
+                truncated={self.expanded_data.truncated_varcomps.clone()}
+                confounded={self.expanded_data.confounded_terms.clone()}
+            />
+            </>
+        }
+    }
+
+    fn view_type1(&self, ctx: &Context<Self>) -> Html {
+        let settings = self.type1_data.settings;
+        let input = |label: &str, value: f64, update: fn(&mut Type1Settings, f64)| {
+            let onchange = ctx.link().batch_callback(move |e: Event| {
+                let target: Option<EventTarget> = e.target();
+                let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+                input.and_then(|i| i.value().parse::<f64>().ok()).map(|v| {
+                    let mut settings = settings;
+                    update(&mut settings, v);
+                    Msg::SetType1Settings(settings)
+                })
+            });
+            html! {
+                <label>
+                    {format!("{} ", label)}
+                    <input type="number" step="any" value={value.to_string()} {onchange} />
+                </label>
+            }
+        };
+        let study = &self.type1_data;
+        html! {
+            <>
+            <div class="options">
+                { input("Reference", settings.reference, |s, v| s.reference = v) }
+                { input("Tolerance", settings.tolerance, |s, v| s.tolerance = v) }
+                { input("K %", settings.k_percent, |s, v| s.k_percent = v) }
+                { input("L", settings.l, |s, v| s.l = v) }
+            </div>
+            <Type1Table
+                n={study.n}
+                reference={settings.reference}
+                mean={study.mean}
+                stddev={study.stddev}
+                bias={study.bias}
+                t={study.t}
+                p_bias={study.p_bias}
+                cg={study.cg}
+                cgk={study.cgk}
+                var_repeatability={study.var_repeatability}
+                var_repeatability_bias={study.var_repeatability_bias}
+                n_part={study.parts.len()}
+            />
+            <RunChart
+                values={study.values()}
+                reference={settings.reference}
+                tolerance={settings.tolerance}
+            />
+            </>
+        }
+    }
+
+    fn has_reference(&self) -> bool {
+        self.items.iter().any(|i| i.reference.is_some())
+    }
+
+    fn view_linearity(&self, ctx: &Context<Self>) -> Html {
+        let parse = |e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|i| i.value().parse::<f64>().ok())
+        };
+        let on_process_variation = ctx
+            .link()
+            .batch_callback(move |e: Event| parse(e).map(Msg::SetProcessVariation));
+        let parts: BTreeSet<&String> = self.items.iter().map(|i| &i.part).collect();
+        let view_part = |part: &String| {
+            let name = part.clone();
+            let onchange = ctx.link().batch_callback(move |e: Event| {
+                parse(e).map(|r| Msg::SetPartReference(name.clone(), r))
+            });
+            let value = self
+                .part_references
+                .get(part)
+                .copied()
+                .or_else(|| {
+                    self.items
+                        .iter()
+                        .find(|i| &i.part == part)
+                        .and_then(|i| i.reference)
+                })
+                .map(|r| r.to_string())
+                .unwrap_or_default();
+            html! {
+                <label>
+                    {format!("{} ", part)}
+                    <input type="number" step="any" {value} {onchange} />
+                </label>
+            }
+        };
+        let study = &self.linearity_data;
+        html! {
+            <>
+            <div class="options">
+                <label>
+                    {"Process variation "}
+                    <input
+                        type="number"
+                        step="any"
+                        value={study.process_variation.map(|pv| pv.to_string()).unwrap_or_default()}
+                        onchange={on_process_variation}
+                    />
+                </label>
+            </div>
+            <div class="options">
+                <p>{"Reference value per part"}</p>
+                { for parts.into_iter().map(view_part) }
+            </div>
+            <LinearityTable
+                slope={study.slope}
+                intercept={study.intercept}
+                se_slope={study.se_slope}
+                se_intercept={study.se_intercept}
+                p_slope={study.p_slope}
+                p_intercept={study.p_intercept}
+                s={study.s}
+                r_squared={study.r_squared}
+                linearity={study.linearity}
+                percent_linearity={study.percent_linearity}
+                bias={study.bias.clone()}
+                average_bias={study.average_bias.clone()}
+            />
+            </>
+        }
+    }
+
+    fn has_rating(&self) -> bool {
+        self.items.iter().any(|i| i.rating.is_some())
+    }
+
+    fn view_attribute(&self, ctx: &Context<Self>) -> Html {
+        let on_ordinal = ctx.link().batch_callback(|e: Event| {
+            let target: Option<EventTarget> = e.target();
+            let input = target.and_then(|t| t.dyn_into::<HtmlInputElement>().ok());
+            input.map(|i| Msg::SetOrdinal(i.checked()))
+        });
+        let study = &self.attribute_data;
+        let by_appraiser = |f: fn(&AppraiserAgreement) -> Option<Agreement>| {
+            study
+                .appraisers
+                .iter()
+                .filter_map(|a| Some((a.operator.to_string(), f(a)?)))
+                .collect::<Vec<_>>()
+        };
+        let kappa_by_appraiser = |f: fn(&AppraiserAgreement) -> &Vec<KappaRow>| {
+            study
+                .appraisers
+                .iter()
+                .filter(|a| !f(a).is_empty())
+                .map(|a| (a.operator.to_string(), f(a).clone()))
+                .collect::<Vec<_>>()
+        };
+        let kendall_by_appraiser = |f: fn(&AppraiserAgreement) -> Option<KendallRow>| {
+            study
+                .appraisers
+                .iter()
+                .filter_map(|a| Some((a.operator.to_string(), f(a)?)))
+                .collect::<Vec<_>>()
+        };
+        let kappa_for_all = |kappa: &Vec<KappaRow>| {
+            (!kappa.is_empty())
+                .then(|| ("All".to_owned(), kappa.clone()))
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        html! {
+            <>
+            <div class="options">
+                <label>
+                    <input type="checkbox" checked={study.ordinal} onchange={on_ordinal} />
+                    {" Ordinal ratings"}
+                </label>
+                if !self.has_rating() {
+                    <p>{"Add a Rating column, and optionally a Standard column, to the CSV."}</p>
+                }
+            </div>
+            <AgreementTable
+                title={"Within Appraisers"}
+                agreement={by_appraiser(|a| a.within.clone())}
+                kappa_title={"Fleiss' Kappa"}
+                kappa={kappa_by_appraiser(|a| &a.within_kappa)}
+                kendall_title={"Kendall's Coefficient of Concordance"}
+                kendall={kendall_by_appraiser(|a| a.within_kendall.clone())}
+            />
+            <AgreementTable
+                title={"Each Appraiser vs Standard"}
+                agreement={by_appraiser(|a| a.vs_standard.clone())}
+                kappa_title={"Cohen's Kappa"}
+                kappa={kappa_by_appraiser(|a| &a.vs_standard_kappa)}
+                kendall_title={"Kendall's Correlation Coefficient"}
+                kendall={kendall_by_appraiser(|a| a.vs_standard_kendall.clone())}
+            />
+            <AgreementTable
+                title={"Between Appraisers"}
+                agreement={for_all(&study.between)}
+                kappa_title={"Fleiss' Kappa"}
+                kappa={kappa_for_all(&study.between_kappa)}
+                kendall_title={"Kendall's Coefficient of Concordance"}
+                kendall={for_all(&study.between_kendall)}
+            />
+            <AgreementTable
+                title={"All Appraisers vs Standard"}
+                agreement={for_all(&study.all_vs_standard)}
+                kappa_title={"Cohen's Kappa"}
+                kappa={kappa_for_all(&study.all_vs_standard_kappa)}
+                kendall_title={"Kendall's Correlation Coefficient"}
+                kendall={for_all(&study.all_vs_standard_kendall)}
+            />
+            </>
+        }
+    }
+
+    fn study_data(&self) -> Vec<GageStudyData> {
+        self.items
             .iter()
-            .map(|i| GageStudyData::new(&i.part, i.operator, i.trial, i.value))
-            .collect();
-        GageStudyDataSet::new_with_data(data).calculate()
+            .map(|i| {
+                GageStudyData::new(&i.part, i.operator, i.trial, i.value)
+                    .with_reference(i.reference)
+                    .with_rating(i.rating.clone(), i.standard.clone())
+                    .with_factors(i.factors.clone())
+            })
+            .collect()
+    }
+
+    pub fn process_attribute_agreement(&self) -> AttributeAgreement {
+        let mut study = AttributeAgreement::new_with_data(self.study_data());
+        study.ordinal = self.attribute_data.ordinal;
+        study.calculate()
     }
+
+    pub fn process_linearity_study(&self) -> LinearityStudy {
+        let mut study =
+            LinearityStudy::new_with_data(self.study_data(), self.part_references.clone());
+        study.process_variation = self.linearity_data.process_variation;
+        study.calculate()
+    }
+
+    pub fn process_type1_gage_study(&self) -> Type1GageStudy {
+        Type1GageStudy::new_with_data(self.study_data(), self.type1_data.settings).calculate()
+    }
+
+    pub fn process_expanded_gage_study(&self) -> Result<ExpandedGageStudyDataSet, GageError> {
+        let mut gagerr =
+            ExpandedGageStudyDataSet::new_with_data(self.study_data(), self.extra_factors.clone());
+        gagerr.spec_limits = self.spec_limits;
+        gagerr.historical_stddev = self.historical_stddev;
+        gagerr.confidence = self.confidence;
+        gagerr.calculate()
+    }
+
+    pub fn process_nested_gage_study(&self) -> Result<NestedGageStudyDataSet, GageError> {
+        let mut gagerr = NestedGageStudyDataSet::new_with_data(self.study_data());
+        gagerr.spec_limits = self.spec_limits;
+        gagerr.historical_stddev = self.historical_stddev;
+        gagerr.confidence = self.confidence;
+        gagerr.calculate()
+    }
+
+    pub fn process_gage_study(&self) -> Result<GageStudyDataSet, GageError> {
+        let mut gagerr = GageStudyDataSet::new_with_data(self.study_data());
+        gagerr.spec_limits = self.spec_limits;
+        gagerr.historical_stddev = self.historical_stddev;
+        gagerr.confidence = self.confidence;
+        gagerr.varcomp_method = self.varcomp_method;
+        gagerr.pool_interaction = self.pool_interaction;
+        gagerr.alpha_to_remove = self.alpha_to_remove;
+        gagerr.calculate()
+    }
+}
+
+/// Which method the variance components of a crossed study were estimated
+/// with, and why if it was not the one selected.
+fn varcomp_method_note(gagerr: &GageStudyDataSet) -> String {
+    let note = format!("Estimated by {}", gagerr.varcomp_method_used);
+    if !gagerr.balanced {
+        format!("{note} because the design is unbalanced.")
+    } else if gagerr.varcomp_method_used != gagerr.varcomp_method {
+        format!("{note} because {} did not converge.", gagerr.varcomp_method)
+    } else {
+        format!("{note}.")
+    }
+}
+
+/// A single result labelled as applying to all appraisers.
+fn for_all<T: Clone>(result: &Option<T>) -> Vec<(String, T)> {
+    result
+        .iter()
+        .map(|r| ("All".to_owned(), r.clone()))
+        .collect()
 }
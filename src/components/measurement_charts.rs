@@ -0,0 +1,288 @@
+use crate::components::chart_scale::Scale;
+use crate::models::gage_data::GageData;
+use gagerr_core::gage_error::GageError;
+use gagerr_core::gage_study_data::GageStudyData;
+use gagerr_core::measurement_plots::{Boxplot, MeasurementPlotData};
+use std::rc::Rc;
+use yew::prelude::*;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 280.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 110.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+/// Line colors of the operators in the interaction plot, reused past the
+/// last one
+const OPERATOR_COLORS: [&str; 6] = [
+    "#2c3e50", "#c0392b", "#04AA6D", "#e67e22", "#8e44ad", "#2980b9",
+];
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct MeasurementChartsProps {
+    /// Shared with the data table rather than copied on every render
+    pub items: Rc<Vec<GageData>>,
+}
+
+/// The remaining charts of the Gage R&R six-pack: measurements by part,
+/// boxplots of the measurements by operator and the part * operator
+/// interaction plot.
+pub struct MeasurementCharts {
+    pub plots: Result<MeasurementPlotData, GageError>,
+}
+
+impl Component for MeasurementCharts {
+    type Message = ();
+    type Properties = MeasurementChartsProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            plots: Self::calculate(&ctx.props().items),
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.plots = Self::calculate(&ctx.props().items);
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let plots = match &self.plots {
+            Ok(plots) => plots,
+            Err(error) => {
+                return html! {
+                    <div class="data">
+                        <div class="table-header"><h2>{"Measurement Charts"}</h2></div>
+                        <p class="table-note warning">{error.to_string()}</p>
+                    </div>
+                }
+            }
+        };
+        html! {
+            <div class="data chart">
+                <div class="table-header"><h2>{"Measurement by Part"}</h2></div>
+                { Self::by_part(plots) }
+                <div class="table-header"><h2>{"Measurement by Operator"}</h2></div>
+                { Self::by_operator(plots) }
+                <div class="table-header"><h2>{"Parts * Operator Interaction"}</h2></div>
+                { Self::interaction(plots) }
+                <p class="table-note">
+                    {"Boxes span the quartiles of each operator, whiskers reach the furthest \
+                      measurements within 1.5 times the box and the points beyond them are \
+                      outliers. Parallel lines in the interaction plot mean the operators \
+                      agree on every part."}
+                </p>
+            </div>
+        }
+    }
+}
+
+impl MeasurementCharts {
+    fn calculate(items: &[GageData]) -> Result<MeasurementPlotData, GageError> {
+        let data = items
+            .iter()
+            .map(|i| GageStudyData::new(&i.part, i.operator, i.trial, i.value))
+            .collect();
+        MeasurementPlotData::new_with_data(data).calculate()
+    }
+
+    /// Scale with a slot of width one per category, centered on `0..count`.
+    fn categories(count: usize) -> Scale {
+        Scale {
+            min: -0.5,
+            max: count as f64 - 0.5,
+            start: MARGIN_LEFT,
+            end: WIDTH - MARGIN_RIGHT,
+        }
+    }
+
+    /// Axes, value ticks and category labels shared by the charts.
+    fn frame(y: Scale, x: Scale, labels: &[String], title: &str) -> Html {
+        html! {
+            <>
+                { for y.ticks(5).into_iter().map(|t| html! {
+                    <text class="chart-tick" x={(MARGIN_LEFT - 8.0).to_string()} y={(y.map(t) + 4.0).to_string()} text-anchor="end">
+                        {format!("{}", t)}
+                    </text>
+                })}
+                <line class="chart-axis" x1={MARGIN_LEFT.to_string()} x2={MARGIN_LEFT.to_string()}
+                    y1={MARGIN_TOP.to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                <line class="chart-axis" x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                    y1={(HEIGHT - MARGIN_BOTTOM).to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                // Labels only while they fit under the categories
+                if labels.len() <= 40 {
+                    { for labels.iter().enumerate().map(|(i, label)| html! {
+                        <text class="chart-tick" x={x.map(i as f64).to_string()}
+                            y={(HEIGHT - MARGIN_BOTTOM + 14.0).to_string()} text-anchor="middle">{label}</text>
+                    })}
+                }
+                <text class="chart-tick" x={((MARGIN_LEFT + WIDTH - MARGIN_RIGHT) / 2.0).to_string()}
+                    y={(HEIGHT - 6.0).to_string()} text-anchor="middle">{title}</text>
+            </>
+        }
+    }
+
+    fn svg(content: Html) -> Html {
+        html! {
+            <svg viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} width={WIDTH.to_string()} height={HEIGHT.to_string()}>
+                { content }
+            </svg>
+        }
+    }
+
+    fn polyline(class: &'static str, points: impl Iterator<Item = (f64, f64)>) -> Html {
+        let points = points
+            .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        html! { <polyline class={class} points={points} /> }
+    }
+
+    /// Every measurement of each part, with a line through the part means.
+    fn by_part(plots: &MeasurementPlotData) -> Html {
+        let parts = &plots.by_part;
+        let (min, max) = Scale::bounds(parts.iter().flat_map(|p| p.values.iter().copied()));
+        let y = Scale::padded(min, max, HEIGHT - MARGIN_BOTTOM, MARGIN_TOP);
+        let x = Self::categories(parts.len());
+        let labels: Vec<String> = parts.iter().map(|p| p.part.clone()).collect();
+        Self::svg(html! {
+            <>
+                { Self::frame(y, x, &labels, "Part") }
+                { for parts.iter().enumerate().flat_map(|(i, p)| p.values.iter().map(move |v| html! {
+                    <circle class="chart-value" cx={x.map(i as f64).to_string()} cy={y.map(*v).to_string()} r="2.5">
+                        <title>{format!("Part {}: {:.6}", p.part, v)}</title>
+                    </circle>
+                }))}
+                { Self::polyline("chart-mean", parts.iter().enumerate().map(|(i, p)| (x.map(i as f64), y.map(p.mean)))) }
+                { for parts.iter().enumerate().map(|(i, p)| html! {
+                    <circle class="chart-point" cx={x.map(i as f64).to_string()} cy={y.map(p.mean).to_string()} r="3.5">
+                        <title>{format!("Part {} mean: {:.6}", p.part, p.mean)}</title>
+                    </circle>
+                })}
+            </>
+        })
+    }
+
+    /// A boxplot per operator, with a line through the operator means.
+    fn by_operator(plots: &MeasurementPlotData) -> Html {
+        let boxes = &plots.by_operator;
+        let (min, max) = Scale::bounds(boxes.iter().flat_map(|b| {
+            [b.lower_whisker, b.upper_whisker]
+                .into_iter()
+                .chain(b.outliers.iter().copied())
+        }));
+        let y = Scale::padded(min, max, HEIGHT - MARGIN_BOTTOM, MARGIN_TOP);
+        let x = Self::categories(boxes.len());
+        let half = ((x.map(1.0) - x.map(0.0)) * 0.25).min(40.0);
+        let labels: Vec<String> = boxes.iter().map(|b| b.operator.to_string()).collect();
+        let boxplot = |i: usize, b: &Boxplot| {
+            let center = x.map(i as f64);
+            let title = format!(
+                "Operator {}: Q1 {:.6}, median {:.6}, Q3 {:.6}, mean {:.6}",
+                b.operator, b.q1, b.median, b.q3, b.mean
+            );
+            let hline = |value: f64, width: f64, class: &'static str| {
+                html! {
+                    <line class={class} x1={(center - width).to_string()} x2={(center + width).to_string()}
+                        y1={y.map(value).to_string()} y2={y.map(value).to_string()} />
+                }
+            };
+            let vline = |from: f64, to: f64| {
+                html! {
+                    <line class="chart-axis" x1={center.to_string()} x2={center.to_string()}
+                        y1={y.map(from).to_string()} y2={y.map(to).to_string()} />
+                }
+            };
+            html! {
+                <>
+                    { vline(b.lower_whisker, b.q1) }
+                    { vline(b.q3, b.upper_whisker) }
+                    { hline(b.lower_whisker, half / 2.0, "chart-axis") }
+                    { hline(b.upper_whisker, half / 2.0, "chart-axis") }
+                    <rect class="chart-box" x={(center - half).to_string()} y={y.map(b.q3).to_string()}
+                        width={(2.0 * half).to_string()} height={(y.map(b.q1) - y.map(b.q3)).max(0.0).to_string()}>
+                        <title>{title}</title>
+                    </rect>
+                    { hline(b.median, half, "chart-median") }
+                    { for b.outliers.iter().map(|v| html! {
+                        <circle class="chart-outlier" cx={center.to_string()} cy={y.map(*v).to_string()} r="3">
+                            <title>{format!("Operator {}: {:.6}", b.operator, v)}</title>
+                        </circle>
+                    })}
+                </>
+            }
+        };
+        Self::svg(html! {
+            <>
+                { Self::frame(y, x, &labels, "Operator") }
+                { for boxes.iter().enumerate().map(|(i, b)| boxplot(i, b)) }
+                { Self::polyline("chart-mean", boxes.iter().enumerate().map(|(i, b)| (x.map(i as f64), y.map(b.mean)))) }
+                { for boxes.iter().enumerate().map(|(i, b)| html! {
+                    <circle class="chart-point" cx={x.map(i as f64).to_string()} cy={y.map(b.mean).to_string()} r="3.5">
+                        <title>{format!("Operator {} mean: {:.6}", b.operator, b.mean)}</title>
+                    </circle>
+                })}
+            </>
+        })
+    }
+
+    /// A line of the part averages of each operator. Parts an operator did
+    /// not measure break the line.
+    fn interaction(plots: &MeasurementPlotData) -> Html {
+        let lines = &plots.interaction;
+        let (min, max) =
+            Scale::bounds(lines.iter().flat_map(|l| l.means.iter().flatten().copied()));
+        let y = Scale::padded(min, max, HEIGHT - MARGIN_BOTTOM, MARGIN_TOP);
+        let x = Self::categories(plots.by_part.len());
+        let labels: Vec<String> = plots.by_part.iter().map(|p| p.part.clone()).collect();
+        let line = |k: usize, means: &[Option<f64>]| {
+            let color = OPERATOR_COLORS[k % OPERATOR_COLORS.len()];
+            let mut runs: Vec<Vec<(f64, f64)>> = vec![Vec::new()];
+            for (i, mean) in means.iter().enumerate() {
+                match mean {
+                    Some(mean) => runs
+                        .last_mut()
+                        .unwrap()
+                        .push((x.map(i as f64), y.map(*mean))),
+                    None => runs.push(Vec::new()),
+                }
+            }
+            html! {
+                <>
+                    { for runs.into_iter().filter(|r| r.len() > 1).map(|run| {
+                        let points = run
+                            .iter()
+                            .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        html! { <polyline class="chart-series" stroke={color} points={points} /> }
+                    })}
+                    { for means.iter().enumerate().filter_map(|(i, mean)| mean.map(|m| (i, m))).map(|(i, mean)| html! {
+                        <circle cx={x.map(i as f64).to_string()} cy={y.map(mean).to_string()} r="3" fill={color}>
+                            <title>{format!("Part {}, operator {}: {:.6}", labels[i], lines[k].operator, mean)}</title>
+                        </circle>
+                    })}
+                </>
+            }
+        };
+        Self::svg(html! {
+            <>
+                { Self::frame(y, x, &labels, "Part") }
+                { for lines.iter().enumerate().map(|(k, l)| line(k, &l.means)) }
+                { for lines.iter().enumerate().map(|(k, l)| {
+                    let top = MARGIN_TOP + 18.0 * k as f64;
+                    let left = WIDTH - MARGIN_RIGHT + 15.0;
+                    html! {
+                        <>
+                            <line x1={left.to_string()} x2={(left + 20.0).to_string()} y1={top.to_string()} y2={top.to_string()}
+                                stroke={OPERATOR_COLORS[k % OPERATOR_COLORS.len()]} stroke-width="2" />
+                            <text class="chart-label" x={(left + 26.0).to_string()} y={(top + 4.0).to_string()}>
+                                {format!("Operator {}", l.operator)}
+                            </text>
+                        </>
+                    }
+                })}
+            </>
+        })
+    }
+}
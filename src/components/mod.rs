@@ -1,5 +1,18 @@
+pub mod agreement_table;
 pub mod anova_table;
+pub mod chart_scale;
+pub mod data_quality_panel;
+pub mod expanded_anova_table;
+pub mod expanded_varcomp_table;
+pub mod linearity_table;
 pub mod list_item;
 pub mod list_table;
+pub mod measurement_charts;
+pub mod nested_anova_table;
+pub mod range_method_table;
+pub mod run_chart;
 pub mod study_var_table;
+pub mod type1_table;
+pub mod varcomp_chart;
 pub mod varcomp_table;
+pub mod xbar_r_chart;
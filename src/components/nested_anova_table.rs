@@ -0,0 +1,85 @@
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct NestedAnovaTableProps {
+    pub df_operator: u32,
+    pub df_part: u32,
+    pub df_repeatability: u32,
+    pub df_total: u32,
+    pub ss_operator: f64,
+    pub ss_part: f64,
+    pub ss_repeatability: f64,
+    pub ss_total: f64,
+    pub ms_operator: Option<f64>,
+    pub ms_part: f64,
+    pub ms_repeatability: f64,
+    pub f_operator: Option<f64>,
+    pub f_part: f64,
+    pub p_operator: Option<f64>,
+    pub p_part: f64,
+}
+
+pub struct NestedAnovaTable;
+
+impl Component for NestedAnovaTable {
+    type Message = ();
+    type Properties = NestedAnovaTableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Nested ANOVA"}</h2></div>
+                    <table class="anova-table">
+                        <tr>
+                            <th>{"Source"}</th>
+                            <th>{"DF"}</th>
+                            <th>{"SS"}</th>
+                            <th>{"MS"}</th>
+                            <th>{"F"}</th>
+                            <th>{"P"}</th>
+                        </tr>
+                        if props.df_operator > 0 {
+                            <tr>
+                                <td>{"Operator"}</td>
+                                <td>{format!("{:>-.6}", props.df_operator)}</td>
+                                <td>{format!("{:>-.6}", props.ss_operator)}</td>
+                                <td>{format!("{:>-.6}", props.ms_operator.unwrap_or(0.0))}</td>
+                                <td>{format!("{:>-.6}", props.f_operator.unwrap_or(0.0))}</td>
+                                <td>{format!("{:>-.6}", props.p_operator.unwrap_or(0.0))}</td>
+                            </tr>
+                        }
+                        <tr>
+                            <td>{"Part (Operator)"}</td>
+                            <td>{format!("{:>-.6}", props.df_part)}</td>
+                            <td>{format!("{:>-.6}", props.ss_part)}</td>
+                            <td>{format!("{:>-.6}", props.ms_part)}</td>
+                            <td>{format!("{:>-.6}", props.f_part)}</td>
+                            <td>{format!("{:>-.6}", props.p_part)}</td>
+                        </tr>
+                        <tr>
+                            <td>{"Repeatability"}</td>
+                            <td>{format!("{:>-.6}", props.df_repeatability)}</td>
+                            <td>{format!("{:>-.6}", props.ss_repeatability)}</td>
+                            <td>{format!("{:>-.6}", props.ms_repeatability)}</td>
+                            <td>{""}</td>
+                            <td>{""}</td>
+                        </tr>
+                        <tr>
+                            <td>{"Total"}</td>
+                            <td>{format!("{:>-.6}", props.df_total)}</td>
+                            <td>{format!("{:>-.6}", props.ss_total)}</td>
+                            <td>{""}</td>
+                            <td>{""}</td>
+                            <td>{""}</td>
+                        </tr>
+                    </table>
+                </div>
+            </>
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::components::study_var_table::ndc_note;
+use gagerr_core::gage_study_data_set::RangeMethod;
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct RangeMethodTableProps {
+    pub range_method: Option<RangeMethod>,
+}
+
+pub struct RangeMethodTable;
+
+impl Component for RangeMethodTable {
+    type Message = ();
+    type Properties = RangeMethodTableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let rm = match &ctx.props().range_method {
+            Some(rm) => rm,
+            None => {
+                return html! {
+                    <div class="data">
+                        <div class="table-header"><h2>{"Average and Range Method"}</h2></div>
+                        <p class="table-note">
+                            {"The Average and Range method supports 2 to 15 trials and parts."}
+                        </p>
+                    </div>
+                }
+            }
+        };
+        let row = |source: &str, sd: f64| {
+            html! {
+                <tr>
+                    <td>{source}</td>
+                    <td>{format!("{:>-.6}", sd)}</td>
+                    <td>{format!("{:>-.2}%", sd / rm.tv * 100.0)}</td>
+                </tr>
+            }
+        };
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Average and Range Method"}</h2></div>
+                <table class="studyvar-table">
+                    <thead>
+                        <tr>
+                            <th>{"Source"}</th>
+                            <th>{"StdDev (SD)"}</th>
+                            <th>{"% Total Variation"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { row("Repeatability (EV)", rm.ev) }
+                        { row("Reproducibility (AV)", rm.av) }
+                        { row("Gage R&R (GRR)", rm.grr) }
+                        { row("Part Variation (PV)", rm.pv) }
+                        { row("Total Variation (TV)", rm.tv) }
+                    </tbody>
+                </table>
+                <p class="table-note">
+                    {format!(
+                        "R̄ = {:.6}, X̄diff = {:.6}, Rp = {:.6}, K1 = {:.4}, K2 = {}, K3 = {:.4}",
+                        rm.r_bar,
+                        rm.x_diff,
+                        rm.r_part,
+                        rm.k1,
+                        rm.k2.map(|k| format!("{:.4}", k)).unwrap_or_else(|| "-".to_owned()),
+                        rm.k3,
+                    )}
+                </p>
+                { ndc_note(rm.ndc, None) }
+                </div>
+            </>
+        }
+    }
+}
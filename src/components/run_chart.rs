@@ -0,0 +1,90 @@
+use crate::components::chart_scale::Scale;
+use yew::prelude::*;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 90.0;
+const MARGIN_TOP: f64 = 20.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct RunChartProps {
+    pub values: Vec<f64>,
+    pub reference: f64,
+    pub tolerance: f64,
+}
+
+/// Measurements in run order against the reference value and the limits
+/// reference ± 0.1 × tolerance.
+pub struct RunChart;
+
+impl Component for RunChart {
+    type Message = ();
+    type Properties = RunChartProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let lower = props.reference - 0.1 * props.tolerance;
+        let upper = props.reference + 0.1 * props.tolerance;
+        let (min, max) = Scale::bounds(props.values.iter().copied().chain([lower, upper]));
+        let y = Scale::padded(min, max, HEIGHT - MARGIN_BOTTOM, MARGIN_TOP);
+        let x = Scale::padded(
+            1.0,
+            props.values.len().max(1) as f64,
+            MARGIN_LEFT,
+            WIDTH - MARGIN_RIGHT,
+        );
+        let points = props
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{:.2},{:.2}", x.map(i as f64 + 1.0), y.map(*v)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let hline = |value: f64, label: String, class: &'static str| {
+            html! {
+                <>
+                    <line class={class} x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                        y1={y.map(value).to_string()} y2={y.map(value).to_string()} />
+                    <text class="chart-label" x={(WIDTH - MARGIN_RIGHT + 5.0).to_string()}
+                        y={(y.map(value) + 4.0).to_string()}>{label}</text>
+                </>
+            }
+        };
+        html! {
+            <div class="data chart">
+                <div class="table-header"><h2>{"Run Chart"}</h2></div>
+                <svg viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} width={WIDTH.to_string()} height={HEIGHT.to_string()}>
+                    { for y.ticks(6).into_iter().map(|t| html! {
+                        <text class="chart-tick" x={(MARGIN_LEFT - 8.0).to_string()} y={(y.map(t) + 4.0).to_string()} text-anchor="end">
+                            {format!("{}", t)}
+                        </text>
+                    })}
+                    { for x.ticks(10).into_iter().filter(|t| t.fract() == 0.0).map(|t| html! {
+                        <text class="chart-tick" x={x.map(t).to_string()} y={(HEIGHT - MARGIN_BOTTOM + 18.0).to_string()} text-anchor="middle">
+                            {format!("{}", t)}
+                        </text>
+                    })}
+                    <line class="chart-axis" x1={MARGIN_LEFT.to_string()} x2={MARGIN_LEFT.to_string()}
+                        y1={MARGIN_TOP.to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                    <line class="chart-axis" x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                        y1={(HEIGHT - MARGIN_BOTTOM).to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                    { hline(props.reference, "Ref".to_owned(), "chart-center") }
+                    { hline(upper, "Ref + 0.1 Tol".to_owned(), "chart-limit") }
+                    { hline(lower, "Ref - 0.1 Tol".to_owned(), "chart-limit") }
+                    <polyline class="chart-series" points={points} />
+                    { for props.values.iter().enumerate().map(|(i, v)| html! {
+                        <circle class="chart-point" cx={x.map(i as f64 + 1.0).to_string()} cy={y.map(*v).to_string()} r="3" />
+                    })}
+                    <text class="chart-tick" x={((MARGIN_LEFT + WIDTH - MARGIN_RIGHT) / 2.0).to_string()}
+                        y={(HEIGHT - 5.0).to_string()} text-anchor="middle">{"Observation"}</text>
+                </svg>
+            </div>
+        }
+    }
+}
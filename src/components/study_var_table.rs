@@ -1,12 +1,26 @@
+use gagerr_core::confidence_interval::{Interval, VarCompIntervals};
 use yew::prelude::*;
 #[derive(Properties, PartialEq, Clone)]
 pub struct StudyVarTableProps {
     pub stddev_part: f64,
     pub stddev_operator: Option<f64>,
+    pub stddev_part_operator: Option<f64>,
     pub stddev_total_gagerr: f64,
     pub stddev_total: f64,
     pub stddev_repeatability: f64,
+    pub stddev_reproducibility: Option<f64>,
     pub study_var: f64,
+    pub ndc: u32,
+    pub tolerance: Option<f64>,
+    pub historical_stddev: Option<f64>,
+    pub stddev_part_process: Option<f64>,
+    pub intervals: Option<VarCompIntervals>,
+}
+
+impl Default for StudyVarTableProps {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StudyVarTableProps {
@@ -14,10 +28,17 @@ impl StudyVarTableProps {
         Self {
             stddev_part: 0.0,
             stddev_operator: None,
+            stddev_part_operator: None,
             stddev_total_gagerr: 0.0,
             stddev_total: 0.0,
             stddev_repeatability: 0.0,
+            stddev_reproducibility: None,
             study_var: 0.0,
+            ndc: 0,
+            tolerance: None,
+            historical_stddev: None,
+            stddev_part_process: None,
+            intervals: None,
         }
     }
 }
@@ -25,24 +46,39 @@ impl StudyVarTableProps {
 pub struct StudyVarTable {
     pub stddev_part: f64,
     pub stddev_operator: Option<f64>,
+    pub stddev_part_operator: Option<f64>,
     pub stddev_total_gagerr: f64,
     pub stddev_total: f64,
     pub stddev_repeatability: f64,
+    pub stddev_reproducibility: Option<f64>,
     pub study_var: f64,
+    pub ndc: u32,
+    pub tolerance: Option<f64>,
+    pub historical_stddev: Option<f64>,
+    pub stddev_part_process: Option<f64>,
+    pub intervals: Option<VarCompIntervals>,
 }
 
 impl Component for StudyVarTable {
     type Message = ();
     type Properties = StudyVarTableProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = ctx.props().clone();
         Self {
-            stddev_part: 0.0,
-            stddev_operator: None,
-            stddev_total_gagerr: 0.0,
-            stddev_total: 0.0,
-            stddev_repeatability: 0.0,
-            study_var: 5.15,
+            stddev_part: props.stddev_part,
+            stddev_operator: props.stddev_operator,
+            stddev_part_operator: props.stddev_part_operator,
+            stddev_total_gagerr: props.stddev_total_gagerr,
+            stddev_total: props.stddev_total,
+            stddev_repeatability: props.stddev_repeatability,
+            stddev_reproducibility: props.stddev_reproducibility,
+            study_var: props.study_var,
+            ndc: props.ndc,
+            tolerance: props.tolerance,
+            historical_stddev: props.historical_stddev,
+            stddev_part_process: props.stddev_part_process,
+            intervals: props.intervals,
         }
     }
 
@@ -50,9 +86,17 @@ impl Component for StudyVarTable {
         let props = ctx.props().clone();
         self.stddev_part = props.stddev_part;
         self.stddev_operator = props.stddev_operator;
+        self.stddev_part_operator = props.stddev_part_operator;
         self.stddev_total_gagerr = props.stddev_total_gagerr;
         self.stddev_total = props.stddev_total;
         self.stddev_repeatability = props.stddev_repeatability;
+        self.stddev_reproducibility = props.stddev_reproducibility;
+        self.study_var = props.study_var;
+        self.ndc = props.ndc;
+        self.tolerance = props.tolerance;
+        self.historical_stddev = props.historical_stddev;
+        self.stddev_part_process = props.stddev_part_process;
+        self.intervals = props.intervals;
         true
     }
 
@@ -61,6 +105,8 @@ impl Component for StudyVarTable {
     }
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
+        let ci = self.intervals.as_ref();
+        let ci_header = ci.map(|ci| format!("{:.0}% CI", ci.confidence * 100.0));
         html! {
             <>
                 <div class="data">
@@ -70,39 +116,117 @@ impl Component for StudyVarTable {
                         <tr>
                             <th>{"Source"}</th>
                             <th>{"StdDev (SD)"}</th>
+                            if let Some(h) = &ci_header {
+                                <th>{h}</th>
+                            }
                             <th>{format!("Study Var ({:.2} x SD)", self.study_var)}</th>
                             <th>{"% Study Var"}</th>
+                            if let Some(h) = &ci_header {
+                                <th>{h}</th>
+                            }
+                            if self.historical_stddev.is_some() {
+                                <th>{"% Process"}</th>
+                            }
+                            if self.tolerance.is_some() {
+                                <th>{"% Tolerance"}</th>
+                            }
                         </tr>
                     </thead>
                     <tbody>
-                        <tr>
-                            <td>{"Total Gage R&R"}</td>
-                            <td>{format!("{:>-.6}", self.stddev_total_gagerr)}</td>
-                            <td>{format!("{:>-.6}", self.stddev_total_gagerr * self.study_var)}</td>
-                            <td>{format!("{:>-.2}%", self.stddev_total_gagerr / self.stddev_total * 100.0)}</td>
-                        </tr>
-                        <tr>
-                            <td>{"Repeatability"}</td>
-                            <td>{format!("{:>-.6}", self.stddev_repeatability)}</td>
-                            <td>{format!("{:>-.6}", self.stddev_repeatability * self.study_var)}</td>
-                            <td>{format!("{:>-.2}%", self.stddev_repeatability / self.stddev_total * 100.0)}</td>
-                        </tr>
-                        <tr>
-                            <td>{"Part-To-Part"}</td>
-                            <td>{format!("{:>-.6}", self.stddev_part)}</td>
-                            <td>{format!("{:>-.6}", self.stddev_part * self.study_var)}</td>
-                            <td>{format!("{:>-.2}%", self.stddev_part / self.stddev_total * 100.0)}</td>
-                        </tr>
-                        <tr>
-                            <td>{"Total Variation"}</td>
-                            <td>{format!("{:>-.6}", self.stddev_total)}</td>
-                            <td>{format!("{:>-.6}", self.stddev_total * self.study_var)}</td>
-                            <td>{format!("{:>-.2}%", self.stddev_total / self.stddev_total * 100.0)}</td>
-                        </tr>
+                        { self.row("Total Gage R&R", self.stddev_total_gagerr, None, ci.map(|ci| (ci.total_gagerr, Some(ci.contribution.total_gagerr)))) }
+                        { self.row("Repeatability", self.stddev_repeatability, None, ci.map(|ci| (ci.repeatability, Some(ci.contribution.repeatability)))) }
+                        if let Some(sr) = self.stddev_reproducibility {
+                            { self.row("Reproducibility", sr, None, ci.and_then(|ci| ci.reproducibility.map(|i| (i, ci.contribution.reproducibility)))) }
+                        }
+                        if let Some(so) = self.stddev_operator {
+                            { self.row("Operator", so, None, ci.and_then(|ci| ci.operator.map(|i| (i, ci.contribution.operator)))) }
+                        }
+                        if let Some(spo) = self.stddev_part_operator {
+                            { self.row("Parts * Operator", spo, None, ci.and_then(|ci| ci.part_operator.map(|i| (i, ci.contribution.part_operator)))) }
+                        }
+                        { self.row("Part-To-Part", self.stddev_part, self.stddev_part_process, ci.map(|ci| (ci.part, Some(ci.contribution.part)))) }
+                        { self.row("Total Variation", self.stddev_total, self.historical_stddev, ci.map(|ci| (ci.total, None))) }
                     </tbody>
                 </table>
+                if let (Some(h), Some(sp)) = (self.historical_stddev, self.stddev_part_process) {
+                    <p class="table-note">
+                        {format!(
+                            "% Process uses the historical standard deviation {:.6}, which leaves a part-to-part standard deviation of {:.6}.",
+                            h, sp,
+                        )}
+                    </p>
+                }
+                { ndc_note(self.ndc, ci.map(|ci| ci.ndc)) }
                 </div>
             </>
         }
     }
 }
+
+impl StudyVarTable {
+    /// Row of the table. `process_sd` replaces `sd` in the % Process column
+    /// for the sources that are recomputed from the historical standard
+    /// deviation, and `variance` holds the confidence intervals of the
+    /// variance component and of its share of the total variance, if it is
+    /// not the total.
+    fn row(
+        &self,
+        source: &str,
+        sd: f64,
+        process_sd: Option<f64>,
+        variance: Option<(Interval, Option<Interval>)>,
+    ) -> Html {
+        let with_ci = self.intervals.is_some();
+        let (variance, share) = variance.unzip();
+        let interval = variance.map(Interval::sqrt);
+        html! {
+            <tr>
+                <td>{source}</td>
+                <td>{format!("{:>-.6}", sd)}</td>
+                if with_ci {
+                    <td>{interval.map(|i| format!("({:.6}, {:.6})", i.lower, i.upper)).unwrap_or_default()}</td>
+                }
+                <td>{format!("{:>-.6}", sd * self.study_var)}</td>
+                <td>{format!("{:>-.2}%", sd / self.stddev_total * 100.0)}</td>
+                if with_ci {
+                    <td>
+                        {share
+                            .flatten()
+                            .map(|i| i.sqrt().scale(100.0))
+                            .map(|i| format!("({:.2}%, {:.2}%)", i.lower, i.upper))
+                            .unwrap_or_default()}
+                    </td>
+                }
+                if let Some(historical) = self.historical_stddev {
+                    <td>{format!("{:>-.2}%", process_sd.unwrap_or(sd) / historical * 100.0)}</td>
+                }
+                if let Some(tolerance) = self.tolerance {
+                    <td>{format!("{:>-.2}%", sd * self.study_var / tolerance * 100.0)}</td>
+                }
+            </tr>
+        }
+    }
+}
+
+/// Number of distinct categories against the AIAG guideline of at least 5,
+/// with its confidence interval when there is one.
+pub fn ndc_note(ndc: u32, interval: Option<Interval>) -> Html {
+    let verdict = if ndc >= 5 {
+        "acceptable"
+    } else {
+        "below the AIAG minimum of 5"
+    };
+    html! {
+        <p class={classes!("table-note", (ndc < 5).then_some("warning"))}>
+            {format!("Number of distinct categories = {} ({})", ndc, verdict)}
+            if let Some(i) = interval {
+                // The upper limit is unbounded when the gage variance can be zero
+                {format!(
+                    ", CI ({}, {})",
+                    i.lower,
+                    if i.upper.is_finite() { i.upper.to_string() } else { "∞".to_owned() },
+                )}
+            }
+        </p>
+    }
+}
@@ -0,0 +1,72 @@
+use yew::prelude::*;
+#[derive(Properties, PartialEq, Clone)]
+pub struct Type1TableProps {
+    pub n: u32,
+    pub reference: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub bias: f64,
+    pub t: f64,
+    pub p_bias: f64,
+    pub cg: f64,
+    pub cgk: f64,
+    pub var_repeatability: f64,
+    pub var_repeatability_bias: f64,
+    pub n_part: usize,
+}
+
+pub struct Type1Table;
+
+impl Component for Type1Table {
+    type Message = ();
+    type Properties = Type1TableProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let row = |name: &str, value: String| {
+            html! {
+                <tr>
+                    <td>{name}</td>
+                    <td>{value}</td>
+                </tr>
+            }
+        };
+        html! {
+            <>
+                <div class="data">
+                <div class="table-header"><h2>{"Type 1 Gage Study"}</h2></div>
+                <table class="studyvar-table">
+                    <thead>
+                        <tr>
+                            <th>{"Statistic"}</th>
+                            <th>{"Value"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { row("Reference", format!("{:>-.6}", props.reference)) }
+                        { row("Mean", format!("{:>-.6}", props.mean)) }
+                        { row("StdDev", format!("{:>-.6}", props.stddev)) }
+                        { row("Observations", props.n.to_string()) }
+                        { row("Bias", format!("{:>-.6}", props.bias)) }
+                        { row("T", format!("{:>-.6}", props.t)) }
+                        { row("P-Value", format!("{:>-.6}", props.p_bias)) }
+                        { row("Cg", format!("{:>-.2}", props.cg)) }
+                        { row("Cgk", format!("{:>-.2}", props.cgk)) }
+                        { row("% Var (Repeatability)", format!("{:>-.2}%", props.var_repeatability)) }
+                        { row("% Var (Repeatability and Bias)", format!("{:>-.2}%", props.var_repeatability_bias)) }
+                    </tbody>
+                </table>
+                if props.n_part > 1 {
+                    <p class="table-note">
+                        {format!("The data contains {} parts; a Type 1 study expects a single reference part.", props.n_part)}
+                    </p>
+                }
+                </div>
+            </>
+        }
+    }
+}
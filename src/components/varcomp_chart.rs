@@ -0,0 +1,174 @@
+use crate::components::chart_scale::Scale;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, EventTarget};
+use yew::prelude::*;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 360.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 50.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+const FONT: &str = "font-family: sans-serif; font-size: 12px";
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct VarCompChartProps {
+    pub varcomp_total_gagerr: f64,
+    pub varcomp_repeatability: f64,
+    pub varcomp_reproducibility: Option<f64>,
+    pub varcomp_part: f64,
+    pub varcomp_total: f64,
+    pub stddev_total_gagerr: f64,
+    pub stddev_repeatability: f64,
+    pub stddev_reproducibility: Option<f64>,
+    pub stddev_part: f64,
+    pub stddev_total: f64,
+    pub study_var: f64,
+    pub tolerance: Option<f64>,
+}
+
+/// Minitab style "Components of Variation" chart: % Contribution,
+/// % Study Var and, with a tolerance, % Tolerance of each source. The SVG is
+/// styled with attributes rather than classes so that the downloaded file
+/// looks the same on its own.
+pub struct VarCompChart {
+    svg: NodeRef,
+}
+
+impl Component for VarCompChart {
+    type Message = ();
+    type Properties = VarCompChartProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            svg: NodeRef::default(),
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let mut series = vec![("% Contribution", "#2c3e50"), ("% Study Var", "#04AA6D")];
+        if props.tolerance.is_some() {
+            series.push(("% Tolerance", "#e67e22"));
+        }
+        let percents = |varcomp: f64, sd: f64| {
+            let mut percents = vec![
+                varcomp / props.varcomp_total * 100.0,
+                sd / props.stddev_total * 100.0,
+            ];
+            if let Some(tolerance) = props.tolerance {
+                percents.push(sd * props.study_var / tolerance * 100.0);
+            }
+            percents
+        };
+        let mut groups = vec![
+            (
+                "Gage R&R",
+                percents(props.varcomp_total_gagerr, props.stddev_total_gagerr),
+            ),
+            (
+                "Repeat",
+                percents(props.varcomp_repeatability, props.stddev_repeatability),
+            ),
+        ];
+        if let (Some(v), Some(sd)) = (props.varcomp_reproducibility, props.stddev_reproducibility) {
+            groups.push(("Reprod", percents(v, sd)));
+        }
+        groups.push((
+            "Part-to-Part",
+            percents(props.varcomp_part, props.stddev_part),
+        ));
+
+        // Bars start at zero, and % Tolerance can go past 100
+        let (_, max) = Scale::bounds(groups.iter().flat_map(|(_, p)| p.iter().copied()));
+        let y = Scale {
+            min: 0.0,
+            max: max.max(100.0) * 1.05,
+            start: HEIGHT - MARGIN_BOTTOM,
+            end: MARGIN_TOP,
+        };
+        let group_width = (WIDTH - MARGIN_LEFT - MARGIN_RIGHT) / groups.len() as f64;
+        let bar_width = group_width * 0.8 / series.len() as f64;
+
+        let svg = self.svg.clone();
+        let ondownload = Callback::from(move |e: MouseEvent| {
+            let target: Option<EventTarget> = e.target();
+            let link = target.and_then(|t| t.dyn_into::<Element>().ok());
+            if let (Some(link), Some(svg)) = (link, svg.cast::<Element>()) {
+                // Set just before the browser follows the link
+                let _ = link.set_attribute("href", &svg_data_url(&svg.outer_html()));
+            }
+        });
+
+        html! {
+            <div class="data chart">
+                <div class="table-header"><h2>{"Components of Variation"}</h2></div>
+                <svg ref={self.svg.clone()} xmlns="http://www.w3.org/2000/svg"
+                    viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} width={WIDTH.to_string()} height={HEIGHT.to_string()}>
+                    <rect x="0" y="0" width={WIDTH.to_string()} height={HEIGHT.to_string()} fill="white" />
+                    { for y.ticks(5).into_iter().map(|t| html! {
+                        <>
+                            <line x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                                y1={y.map(t).to_string()} y2={y.map(t).to_string()} stroke="#ddd" />
+                            <text x={(MARGIN_LEFT - 8.0).to_string()} y={(y.map(t) + 4.0).to_string()}
+                                text-anchor="end" style={FONT}>{format!("{}", t)}</text>
+                        </>
+                    })}
+                    { for groups.iter().enumerate().map(|(i, (source, percents))| {
+                        let left = MARGIN_LEFT + group_width * (i as f64 + 0.1);
+                        html! {
+                            <>
+                                { for percents.iter().zip(series.iter()).enumerate().map(|(j, (p, (name, color)))| html! {
+                                    <rect x={(left + bar_width * j as f64).to_string()} y={y.map(*p).to_string()}
+                                        width={bar_width.to_string()} height={(y.map(0.0) - y.map(*p)).max(0.0).to_string()}
+                                        fill={*color}>
+                                        <title>{format!("{} {}: {:.2}%", source, name, p)}</title>
+                                    </rect>
+                                })}
+                                <text x={(MARGIN_LEFT + group_width * (i as f64 + 0.5)).to_string()}
+                                    y={(HEIGHT - MARGIN_BOTTOM + 18.0).to_string()} text-anchor="middle" style={FONT}>
+                                    {*source}
+                                </text>
+                            </>
+                        }
+                    })}
+                    <line x1={MARGIN_LEFT.to_string()} x2={MARGIN_LEFT.to_string()}
+                        y1={MARGIN_TOP.to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} stroke="#333" />
+                    <line x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                        y1={(HEIGHT - MARGIN_BOTTOM).to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} stroke="#333" />
+                    <text x="20" y={((MARGIN_TOP + HEIGHT - MARGIN_BOTTOM) / 2.0).to_string()} text-anchor="middle" style={FONT}
+                        transform={format!("rotate(-90 20 {})", (MARGIN_TOP + HEIGHT - MARGIN_BOTTOM) / 2.0)}>
+                        {"Percent"}
+                    </text>
+                    { for series.iter().enumerate().map(|(j, (name, color))| {
+                        let x = MARGIN_LEFT + 130.0 * j as f64;
+                        html! {
+                            <>
+                                <rect x={x.to_string()} y="15" width="12" height="12" fill={*color} />
+                                <text x={(x + 18.0).to_string()} y="25" style={FONT}>{*name}</text>
+                            </>
+                        }
+                    })}
+                </svg>
+                <a class="download" href="#" download="components-of-variation.svg" onclick={ondownload}>
+                    {"Download SVG"}
+                </a>
+            </div>
+        }
+    }
+}
+
+/// `data:` URL of an SVG document, percent-encoding everything but the
+/// characters that are safe in a URL.
+fn svg_data_url(svg: &str) -> String {
+    let mut url = "data:image/svg+xml;charset=utf-8,".to_owned();
+    for byte in svg.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
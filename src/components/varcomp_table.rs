@@ -1,11 +1,25 @@
+use gagerr_core::confidence_interval::{Interval, VarCompIntervals};
 use yew::prelude::*;
 #[derive(Properties, PartialEq, Clone)]
 pub struct VarCompTableProps {
     pub varcomp_part: f64,
     pub varcomp_operator: Option<f64>,
+    pub varcomp_part_operator: Option<f64>,
     pub varcomp_total_gagerr: f64,
     pub varcomp_total: f64,
     pub varcomp_repeatability: f64,
+    pub varcomp_reproducibility: Option<f64>,
+    pub intervals: Option<VarCompIntervals>,
+    /// How the components were estimated, shown under the table
+    pub note: Option<String>,
+    /// Sources whose negative estimate was set to zero
+    pub truncated: Vec<String>,
+}
+
+impl Default for VarCompTableProps {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl VarCompTableProps {
@@ -13,9 +27,14 @@ impl VarCompTableProps {
         Self {
             varcomp_part: 0.0,
             varcomp_operator: None,
+            varcomp_part_operator: None,
             varcomp_total_gagerr: 0.0,
             varcomp_total: 0.0,
             varcomp_repeatability: 0.0,
+            varcomp_reproducibility: None,
+            intervals: None,
+            note: None,
+            truncated: Vec::new(),
         }
     }
 }
@@ -23,22 +42,33 @@ impl VarCompTableProps {
 pub struct VarCompTable {
     pub varcomp_part: f64,
     pub varcomp_operator: Option<f64>,
+    pub varcomp_part_operator: Option<f64>,
     pub varcomp_total_gagerr: f64,
     pub varcomp_total: f64,
     pub varcomp_repeatability: f64,
+    pub varcomp_reproducibility: Option<f64>,
+    pub intervals: Option<VarCompIntervals>,
+    pub note: Option<String>,
+    pub truncated: Vec<String>,
 }
 
 impl Component for VarCompTable {
     type Message = ();
     type Properties = VarCompTableProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = ctx.props().clone();
         Self {
-            varcomp_part: 0.0,
-            varcomp_operator: None,
-            varcomp_total_gagerr: 0.0,
-            varcomp_total: 0.0,
-            varcomp_repeatability: 0.0,
+            varcomp_part: props.varcomp_part,
+            varcomp_operator: props.varcomp_operator,
+            varcomp_part_operator: props.varcomp_part_operator,
+            varcomp_total_gagerr: props.varcomp_total_gagerr,
+            varcomp_total: props.varcomp_total,
+            varcomp_repeatability: props.varcomp_repeatability,
+            varcomp_reproducibility: props.varcomp_reproducibility,
+            intervals: props.intervals,
+            note: props.note,
+            truncated: props.truncated,
         }
     }
 
@@ -46,9 +76,14 @@ impl Component for VarCompTable {
         let props = ctx.props().clone();
         self.varcomp_part = props.varcomp_part;
         self.varcomp_operator = props.varcomp_operator;
+        self.varcomp_part_operator = props.varcomp_part_operator;
         self.varcomp_total_gagerr = props.varcomp_total_gagerr;
         self.varcomp_total = props.varcomp_total;
         self.varcomp_repeatability = props.varcomp_repeatability;
+        self.varcomp_reproducibility = props.varcomp_reproducibility;
+        self.intervals = props.intervals;
+        self.note = props.note;
+        self.truncated = props.truncated;
         true
     }
 
@@ -57,6 +92,7 @@ impl Component for VarCompTable {
     }
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
+        let ci = self.intervals.as_ref();
         html! {
             <>
                 <div class="data">
@@ -66,34 +102,88 @@ impl Component for VarCompTable {
                         <tr>
                             <th>{"Source"}</th>
                             <th>{"VarComp"}</th>
+                            if let Some(ci) = ci {
+                                <th>{format!("{:.0}% CI", ci.confidence * 100.0)}</th>
+                            }
                             <th>{"% Contribution"}</th>
+                            if let Some(ci) = ci {
+                                <th>{format!("{:.0}% CI", ci.confidence * 100.0)}</th>
+                            }
                         </tr>
                     </thead>
                     <tbody>
-                        <tr>
-                            <td>{"Total Gage R&R"}</td>
-                            <td>{format!("{:>-.6}", self.varcomp_total_gagerr)}</td>
-                            <td>{format!("{:>-.2}%", self.varcomp_total_gagerr / self.varcomp_total * 100.0)}</td>
-                        </tr>
-                        <tr>
-                            <td>{"Repeatability"}</td>
-                            <td>{format!("{:>-.6}", self.varcomp_repeatability)}</td>
-                            <td>{format!("{:>-.2}%", self.varcomp_repeatability / self.varcomp_total * 100.0)}</td>
-                        </tr>
-                        <tr>
-                            <td>{"Part-To-Part"}</td>
-                            <td>{format!("{:>-.6}", self.varcomp_part)}</td>
-                            <td>{format!("{:>-.2}%", self.varcomp_part / self.varcomp_total * 100.0)}</td>
-                        </tr>
-                        <tr>
-                            <td>{"Total Variation"}</td>
-                            <td>{format!("{:>-.6}", self.varcomp_total)}</td>
-                            <td>{format!("{:>-.2}%", self.varcomp_total / self.varcomp_total * 100.0)}</td>
-                        </tr>
+                        { self.row("Total Gage R&R", self.varcomp_total_gagerr, ci.map(|ci| (ci.total_gagerr, Some(ci.contribution.total_gagerr)))) }
+                        { self.row("Repeatability", self.varcomp_repeatability, ci.map(|ci| (ci.repeatability, Some(ci.contribution.repeatability)))) }
+                        if let Some(vr) = self.varcomp_reproducibility {
+                            { self.row("Reproducibility", vr, ci.and_then(|ci| ci.reproducibility.map(|i| (i, ci.contribution.reproducibility)))) }
+                        }
+                        if let Some(vo) = self.varcomp_operator {
+                            { self.row("Operator", vo, ci.and_then(|ci| ci.operator.map(|i| (i, ci.contribution.operator)))) }
+                        }
+                        if let Some(vpo) = self.varcomp_part_operator {
+                            { self.row("Parts * Operator", vpo, ci.and_then(|ci| ci.part_operator.map(|i| (i, ci.contribution.part_operator)))) }
+                        }
+                        { self.row("Part-To-Part", self.varcomp_part, ci.map(|ci| (ci.part, Some(ci.contribution.part)))) }
+                        { self.row("Total Variation", self.varcomp_total, ci.map(|ci| (ci.total, None))) }
                     </tbody>
                 </table>
+                if let Some(note) = &self.note {
+                    <p class="table-note">{note}</p>
+                }
+                { truncated_note(&self.truncated) }
                 </div>
             </>
         }
     }
 }
+
+impl VarCompTable {
+    /// Row of the table. `intervals` are the confidence intervals of the
+    /// variance component and of its share of the total variance; the total
+    /// itself has no interval for its share, which is always 100%.
+    fn row(
+        &self,
+        source: &str,
+        varcomp: f64,
+        intervals: Option<(Interval, Option<Interval>)>,
+    ) -> Html {
+        let with_ci = self.intervals.is_some();
+        let (interval, share) = intervals.unzip();
+        html! {
+            <tr>
+                <td>{source}</td>
+                <td>{format!("{:>-.6}", varcomp)}</td>
+                if with_ci {
+                    <td>{interval.map(|i| format!("({:.6}, {:.6})", i.lower, i.upper)).unwrap_or_default()}</td>
+                }
+                <td>{format!("{:>-.2}%", varcomp / self.varcomp_total * 100.0)}</td>
+                if with_ci {
+                    <td>
+                        {share
+                            .flatten()
+                            .map(|i| i.scale(100.0))
+                            .map(|i| format!("({:.2}%, {:.2}%)", i.lower, i.upper))
+                            .unwrap_or_default()}
+                    </td>
+                }
+            </tr>
+        }
+    }
+}
+
+/// Warning that some variance components were estimated below zero and
+/// reported as zero instead.
+pub fn truncated_note(truncated: &[String]) -> Html {
+    if truncated.is_empty() {
+        return html! {};
+    }
+    html! {
+        <p class="table-note warning">
+            {format!(
+                "Negative estimate set to zero for {}: the variation from this source is too small \
+                 to be distinguished from the error term.",
+                truncated.join(", "),
+            )}
+        </p>
+    }
+}
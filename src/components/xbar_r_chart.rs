@@ -0,0 +1,222 @@
+use crate::components::chart_scale::Scale;
+use crate::models::gage_data::GageData;
+use gagerr_core::gage_error::GageError;
+use gagerr_core::gage_study_data::GageStudyData;
+use gagerr_core::xbar_r_chart::{ControlLimits, Subgroup, XbarRChartData};
+use std::collections::HashMap;
+use std::rc::Rc;
+use yew::prelude::*;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 280.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 110.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct XbarRChartProps {
+    /// Shared with the data table rather than copied on every render
+    pub items: Rc<Vec<GageData>>,
+    /// Called with the part and operator of a point outside the limits when
+    /// it is clicked
+    pub onselect: Callback<(String, u32)>,
+}
+
+/// Xbar chart of the cell averages and R chart of the cell ranges, grouped
+/// by operator. Points outside the control limits link to the first row of
+/// their part and operator in the data table.
+pub struct XbarRChart {
+    pub chart: Result<XbarRChartData, GageError>,
+    /// First line of the file of every part and operator
+    pub rows: HashMap<(String, u32), u64>,
+}
+
+impl Component for XbarRChart {
+    type Message = ();
+    type Properties = XbarRChartProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (chart, rows) = Self::calculate(&ctx.props().items);
+        Self { chart, rows }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        (self.chart, self.rows) = Self::calculate(&ctx.props().items);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let chart = match &self.chart {
+            Ok(chart) => chart,
+            Err(error) => {
+                return html! {
+                    <div class="data">
+                        <div class="table-header"><h2>{"Xbar and R Charts"}</h2></div>
+                        <p class="table-note warning">{error.to_string()}</p>
+                    </div>
+                }
+            }
+        };
+        let means: Vec<f64> = chart.subgroups.iter().map(|s| s.mean).collect();
+        let ranges: Vec<f64> = chart.subgroups.iter().map(|s| s.range).collect();
+        html! {
+            <div class="data chart">
+                <div class="table-header"><h2>{"Xbar Chart by Operator"}</h2></div>
+                { self.panel(ctx, &means, chart.xbar_limits, "X̿") }
+                <div class="table-header"><h2>{"R Chart by Operator"}</h2></div>
+                { self.panel(ctx, &ranges, chart.range_limits, "R̄") }
+                <p class="table-note">
+                    {format!(
+                        "Limits for subgroups of {} trials. Click a point outside the limits to \
+                         go to its measurements.",
+                        chart.subgroup_size,
+                    )}
+                </p>
+            </div>
+        }
+    }
+}
+
+impl XbarRChart {
+    fn calculate(
+        items: &[GageData],
+    ) -> (
+        Result<XbarRChartData, GageError>,
+        HashMap<(String, u32), u64>,
+    ) {
+        let data = items
+            .iter()
+            .map(|i| GageStudyData::new(&i.part, i.operator, i.trial, i.value))
+            .collect();
+        let mut rows: HashMap<(String, u32), u64> = HashMap::new();
+        for item in items.iter() {
+            let row = rows
+                .entry((item.part.clone(), item.operator))
+                .or_insert(item.row);
+            *row = (*row).min(item.row);
+        }
+        (XbarRChartData::new_with_data(data).calculate(), rows)
+    }
+
+    /// One chart of a statistic of every subgroup, in the order of
+    /// `XbarRChartData::subgroups`.
+    fn panel(
+        &self,
+        ctx: &Context<Self>,
+        values: &[f64],
+        limits: ControlLimits,
+        center_label: &str,
+    ) -> Html {
+        let subgroups: &[Subgroup] = self
+            .chart
+            .as_ref()
+            .map(|c| c.subgroups.as_slice())
+            .unwrap_or_default();
+        let (min, max) = Scale::bounds(values.iter().copied().chain([limits.lower, limits.upper]));
+        let y = Scale::padded(min, max, HEIGHT - MARGIN_BOTTOM, MARGIN_TOP);
+        let x = Scale::padded(
+            0.0,
+            values.len().saturating_sub(1).max(1) as f64,
+            MARGIN_LEFT,
+            WIDTH - MARGIN_RIGHT,
+        );
+
+        // Subgroups are sorted by operator, each operator gets its own series
+        let mut operators: Vec<(u32, Vec<usize>)> = Vec::new();
+        for (i, s) in subgroups.iter().enumerate() {
+            match operators.last_mut() {
+                Some((operator, indices)) if *operator == s.operator => indices.push(i),
+                _ => operators.push((s.operator, vec![i])),
+            }
+        }
+
+        let hline = |value: f64, label: String, class: &'static str| {
+            html! {
+                <>
+                    <line class={class} x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                        y1={y.map(value).to_string()} y2={y.map(value).to_string()} />
+                    <text class="chart-label" x={(WIDTH - MARGIN_RIGHT + 5.0).to_string()}
+                        y={(y.map(value) + 4.0).to_string()}>{label}</text>
+                </>
+            }
+        };
+        let point = |i: usize| {
+            let s = &subgroups[i];
+            let v = values[i];
+            let title = format!("Part {}, operator {}: {:.6}", s.part, s.operator, v);
+            let circle = |class: &'static str, r: &'static str| {
+                html! {
+                    <circle class={class} cx={x.map(i as f64).to_string()} cy={y.map(v).to_string()} r={r}>
+                        <title>{title.clone()}</title>
+                    </circle>
+                }
+            };
+            if limits.contains(v) {
+                return circle("chart-point", "3");
+            }
+            let key = (s.part.clone(), s.operator);
+            let href = self
+                .rows
+                .get(&key)
+                .map(|row| format!("#row-{}", row))
+                .unwrap_or_default();
+            let onclick = ctx
+                .props()
+                .onselect
+                .reform(move |_: MouseEvent| key.clone());
+            html! {
+                <a href={href} {onclick}>{ circle("chart-point out-of-control", "5") }</a>
+            }
+        };
+
+        html! {
+            <svg viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} width={WIDTH.to_string()} height={HEIGHT.to_string()}>
+                { for y.ticks(5).into_iter().map(|t| html! {
+                    <text class="chart-tick" x={(MARGIN_LEFT - 8.0).to_string()} y={(y.map(t) + 4.0).to_string()} text-anchor="end">
+                        {format!("{}", t)}
+                    </text>
+                })}
+                <line class="chart-axis" x1={MARGIN_LEFT.to_string()} x2={MARGIN_LEFT.to_string()}
+                    y1={MARGIN_TOP.to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                <line class="chart-axis" x1={MARGIN_LEFT.to_string()} x2={(WIDTH - MARGIN_RIGHT).to_string()}
+                    y1={(HEIGHT - MARGIN_BOTTOM).to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                { hline(limits.center, format!("{}={:.4}", center_label, limits.center), "chart-center") }
+                { hline(limits.upper, format!("UCL={:.4}", limits.upper), "chart-limit") }
+                { hline(limits.lower, format!("LCL={:.4}", limits.lower), "chart-limit") }
+                { for operators.iter().enumerate().map(|(k, (operator, indices))| {
+                    let first = indices[0] as f64;
+                    let last = indices[indices.len() - 1] as f64;
+                    let points = indices
+                        .iter()
+                        .map(|i| format!("{:.2},{:.2}", x.map(*i as f64), y.map(values[*i])))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    html! {
+                        <>
+                            if k > 0 {
+                                <line class="chart-separator" x1={x.map(first - 0.5).to_string()} x2={x.map(first - 0.5).to_string()}
+                                    y1={MARGIN_TOP.to_string()} y2={(HEIGHT - MARGIN_BOTTOM).to_string()} />
+                            }
+                            <text class="chart-tick" x={x.map((first + last) / 2.0).to_string()}
+                                y={(MARGIN_TOP - 10.0).to_string()} text-anchor="middle">
+                                {format!("Operator {}", operator)}
+                            </text>
+                            <polyline class="chart-series" points={points} />
+                        </>
+                    }
+                })}
+                { for (0..values.len()).map(point) }
+                // Part labels only while they fit under the points
+                if subgroups.len() <= 40 {
+                    { for subgroups.iter().enumerate().map(|(i, s)| html! {
+                        <text class="chart-tick" x={x.map(i as f64).to_string()}
+                            y={(HEIGHT - MARGIN_BOTTOM + 14.0).to_string()} text-anchor="middle">{&s.part}</text>
+                    })}
+                }
+                <text class="chart-tick" x={((MARGIN_LEFT + WIDTH - MARGIN_RIGHT) / 2.0).to_string()}
+                    y={(HEIGHT - 6.0).to_string()} text-anchor="middle">{"Part"}</text>
+            </svg>
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use crate::models::gage_data::GageData;
+use csv::{ErrorKind, StringRecord};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A CSV line that could not be read as measurement data.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RejectedRow {
+    pub row: u64,
+    /// Header of the offending column, if the error is about a single field
+    pub column: Option<String>,
+    pub reason: String,
+    /// Fields of the line as read, empty if the line itself was unreadable
+    pub fields: Vec<String>,
+}
+
+impl RejectedRow {
+    pub fn new(error: &csv::Error, headers: &StringRecord, record: Option<&StringRecord>) -> Self {
+        let (column, reason) = match error.kind() {
+            ErrorKind::Deserialize { err, .. } => (
+                err.field()
+                    .and_then(|f| headers.get(f as usize))
+                    .map(|h| h.to_owned()),
+                err.kind().to_string(),
+            ),
+            _ => (None, error.to_string()),
+        };
+        Self {
+            row: error.position().map_or(0, |p| p.line()),
+            column,
+            reason,
+            fields: record
+                .map(|r| r.iter().map(|f| f.to_owned()).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Reads the (possibly edited) fields again, or updates the reason they
+    /// were rejected.
+    pub fn retry(&mut self, headers: &StringRecord) -> Option<GageData> {
+        let record = StringRecord::from(self.fields.clone());
+        match GageData::from_record(&record, headers) {
+            Ok(mut data) => {
+                data.row = self.row;
+                Some(data)
+            }
+            Err(e) => {
+                let row = self.row;
+                *self = Self::new(&e, headers, Some(&record));
+                self.row = row;
+                None
+            }
+        }
+    }
+}
+
+/// A part and operator combination without any measurements.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MissingCell {
+    pub part: String,
+    pub operator: u32,
+}
+
+/// Several rows for the same part, operator and trial.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DuplicateTrial {
+    pub part: String,
+    pub operator: u32,
+    pub trial: u32,
+    pub rows: Vec<u64>,
+}
+
+/// A trial number outside of or missing from `1..=n_trial` in a cell that
+/// has measurements. `row` is the line of an extra trial, `None` for a
+/// missing one.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TrialIssue {
+    pub part: String,
+    pub operator: u32,
+    pub trial: u32,
+    pub row: Option<u64>,
+}
+
+/// Problems with the layout of a crossed study, found on import.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct DataQualityReport {
+    /// Trials every part and operator combination is expected to have, the
+    /// most common number of trials per combination
+    pub n_trial: u32,
+    pub missing_cells: Vec<MissingCell>,
+    pub duplicates: Vec<DuplicateTrial>,
+    pub extra_trials: Vec<TrialIssue>,
+    pub missing_trials: Vec<TrialIssue>,
+}
+
+impl DataQualityReport {
+    pub fn new(items: &[GageData]) -> Self {
+        // Rows of every trial, by part and operator
+        let mut cells: BTreeMap<(&str, u32), BTreeMap<u32, Vec<u64>>> = BTreeMap::new();
+        for item in items.iter() {
+            cells
+                .entry((item.part.as_str(), item.operator))
+                .or_default()
+                .entry(item.trial)
+                .or_default()
+                .push(item.row);
+        }
+        let parts: BTreeSet<&str> = items.iter().map(|i| i.part.as_str()).collect();
+        let operators: BTreeSet<u32> = items.iter().map(|i| i.operator).collect();
+
+        let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+        for trials in cells.values() {
+            *counts.entry(trials.len()).or_default() += 1;
+        }
+        // Ties go to the larger number of trials
+        let n_trial = counts
+            .iter()
+            .max_by_key(|(trials, cells)| (**cells, **trials))
+            .map_or(0, |(trials, _)| *trials as u32);
+
+        let mut report = Self {
+            n_trial,
+            ..Self::default()
+        };
+        for part in parts.iter() {
+            for operator in operators.iter() {
+                let trials = match cells.get(&(*part, *operator)) {
+                    Some(trials) => trials,
+                    None => {
+                        report.missing_cells.push(MissingCell {
+                            part: part.to_string(),
+                            operator: *operator,
+                        });
+                        continue;
+                    }
+                };
+                let issue = |trial: u32, row: Option<u64>| TrialIssue {
+                    part: part.to_string(),
+                    operator: *operator,
+                    trial,
+                    row,
+                };
+                for (trial, rows) in trials.iter() {
+                    if rows.len() > 1 {
+                        report.duplicates.push(DuplicateTrial {
+                            part: part.to_string(),
+                            operator: *operator,
+                            trial: *trial,
+                            rows: rows.clone(),
+                        });
+                    }
+                    if !(1..=n_trial).contains(trial) {
+                        report
+                            .extra_trials
+                            .push(issue(*trial, rows.first().copied()));
+                    }
+                }
+                for trial in (1..=n_trial).filter(|t| !trials.contains_key(t)) {
+                    report.missing_trials.push(issue(trial, None));
+                }
+            }
+        }
+        report
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.missing_cells.is_empty()
+            && self.duplicates.is_empty()
+            && self.extra_trials.is_empty()
+            && self.missing_trials.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csv::ReaderBuilder;
+
+    /// Rows of a CSV file, read as they are on import.
+    fn read(data: &str) -> (Vec<GageData>, Vec<RejectedRow>) {
+        let mut rdr = ReaderBuilder::new().from_reader(data.as_bytes());
+        let headers = rdr.headers().unwrap().clone();
+        let mut items = Vec::new();
+        let mut rejected = Vec::new();
+        for record in rdr.records() {
+            let record = record.unwrap();
+            match GageData::from_record(&record, &headers) {
+                Ok(item) => items.push(item),
+                Err(e) => rejected.push(RejectedRow::new(&e, &headers, Some(&record))),
+            }
+        }
+        (items, rejected)
+    }
+
+    fn item(part: &str, operator: u32, trial: u32, row: u64) -> GageData {
+        let mut item = GageData::new(part, operator, trial, 1.0);
+        item.row = row;
+        item
+    }
+
+    #[test]
+    fn balanced_data_is_clean() {
+        let items: Vec<GageData> = ["A", "B"]
+            .iter()
+            .flat_map(|p| (1..=2).flat_map(move |o| (1..=3).map(move |t| item(p, o, t, 0))))
+            .collect();
+        let report = DataQualityReport::new(&items);
+        assert_eq!(report.n_trial, 3);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn layout_problems_are_reported() {
+        let items = vec![
+            item("A", 1, 1, 2),
+            item("A", 1, 2, 3),
+            item("A", 2, 1, 4),
+            item("A", 2, 2, 5),
+            item("A", 2, 2, 6),
+            item("B", 1, 1, 7),
+            item("B", 1, 2, 8),
+            item("B", 1, 3, 9),
+            item("C", 1, 1, 10),
+            item("C", 1, 2, 11),
+            item("C", 2, 1, 12),
+        ];
+        let report = DataQualityReport::new(&items);
+        assert_eq!(report.n_trial, 2);
+        assert_eq!(
+            report.missing_cells,
+            vec![MissingCell {
+                part: "B".to_owned(),
+                operator: 2,
+            }]
+        );
+        assert_eq!(
+            report.duplicates,
+            vec![DuplicateTrial {
+                part: "A".to_owned(),
+                operator: 2,
+                trial: 2,
+                rows: vec![5, 6],
+            }]
+        );
+        assert_eq!(
+            report.extra_trials,
+            vec![TrialIssue {
+                part: "B".to_owned(),
+                operator: 1,
+                trial: 3,
+                row: Some(9),
+            }]
+        );
+        assert_eq!(
+            report.missing_trials,
+            vec![TrialIssue {
+                part: "C".to_owned(),
+                operator: 2,
+                trial: 2,
+                row: None,
+            }]
+        );
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn unreadable_value_is_rejected_with_its_column() {
+        let (items, rejected) = read("Part,Operator,Trial,Value\nA,1,1,0.5\nA,1,2,abc\n");
+        assert_eq!(items.len(), 1);
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].row, 3);
+        assert_eq!(rejected[0].column.as_deref(), Some("Value"));
+        assert_eq!(rejected[0].fields, vec!["A", "1", "2", "abc"]);
+    }
+
+    #[test]
+    fn missing_value_is_rejected_rather_than_read_as_zero() {
+        let (items, rejected) = read("Part,Operator,Trial,Value\nA,1,1,0.5\nA,1,2,\n");
+        assert_eq!(items.len(), 1);
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].column.as_deref(), Some("Value"));
+
+        let (items, rejected) = read("Part,Operator,Trial,Rating\nA,1,1,Pass\nA,1,2,\n");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].rating.as_deref(), Some("Pass"));
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].row, 3);
+    }
+
+    #[test]
+    fn retry_reads_the_corrected_fields() {
+        let data = "Part,Operator,Trial,Value\nA,1,x,0.5\n";
+        let headers = StringRecord::from(vec!["Part", "Operator", "Trial", "Value"]);
+        let (_, mut rejected) = read(data);
+        let mut row = rejected.remove(0);
+        assert_eq!(row.column.as_deref(), Some("Trial"));
+
+        row.fields[2] = "2".to_owned();
+        row.fields[3] = "oops".to_owned();
+        assert!(row.retry(&headers).is_none());
+        assert_eq!(row.row, 2);
+        assert_eq!(row.column.as_deref(), Some("Value"));
+
+        row.fields[3] = "0.75".to_owned();
+        let item = row.retry(&headers).unwrap();
+        assert_eq!(item.trial, 2);
+        assert_eq!(item.value, 0.75);
+        assert_eq!(item.row, 2);
+    }
+}
@@ -1,4 +1,6 @@
+use csv::StringRecord;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "PascalCase")]
@@ -6,24 +8,99 @@ pub struct GageData {
     pub part: String,
     pub operator: u32,
     pub trial: u32,
+    /// Measured value. Attribute studies have a `Rating` instead, and only
+    /// rows with a rating may leave it out.
+    #[serde(default)]
     pub value: f64,
+    /// Reference (master) value of the part, for linearity and bias studies
+    #[serde(default)]
+    pub reference: Option<f64>,
+    /// Categorical rating, for attribute agreement studies
+    #[serde(default)]
+    pub rating: Option<String>,
+    /// Known rating of the part, for attribute agreement studies
+    #[serde(default)]
+    pub standard: Option<String>,
+    /// Any CSV columns beyond the ones above, keyed by header.
+    #[serde(skip)]
+    pub factors: BTreeMap<String, String>,
+    /// Line of the CSV file the data was read from
+    #[serde(skip)]
+    pub row: u64,
 }
 
-impl GageData {
-    pub fn default() -> Self {
+/// The `Value` column alone, to reject rows that have neither a value nor a
+/// rating rather than read them as 0.
+#[derive(Deserialize)]
+struct MeasuredValue {
+    #[serde(rename = "Value")]
+    _value: f64,
+}
+
+impl Default for GageData {
+    fn default() -> Self {
         Self {
             part: "".to_owned(),
             operator: 1,
             trial: 1,
             value: 0.0,
+            reference: None,
+            rating: None,
+            standard: None,
+            factors: BTreeMap::new(),
+            row: 0,
         }
     }
+}
+
+impl GageData {
+    pub const COLUMNS: [&'static str; 7] = [
+        "Part",
+        "Operator",
+        "Trial",
+        "Value",
+        "Reference",
+        "Rating",
+        "Standard",
+    ];
+
     pub fn new(part: &str, operator: u32, trial: u32, value: f64) -> Self {
         Self {
             part: part.to_owned(),
             operator,
             trial,
             value,
+            reference: None,
+            rating: None,
+            standard: None,
+            factors: BTreeMap::new(),
+            row: 0,
+        }
+    }
+
+    /// Headers that are not one of the standard columns. `Reference` is
+    /// optional and only used by linearity studies, `Rating` and `Standard`
+    /// only by attribute agreement studies.
+    pub fn extra_columns(headers: &StringRecord) -> Vec<String> {
+        headers
+            .iter()
+            .filter(|h| !Self::COLUMNS.contains(h))
+            .map(|h| h.to_owned())
+            .collect()
+    }
+
+    /// Deserialize a CSV record, keeping the values of any extra columns.
+    pub fn from_record(record: &StringRecord, headers: &StringRecord) -> csv::Result<Self> {
+        let mut data: GageData = record.deserialize(Some(headers))?;
+        if data.rating.is_none() {
+            record.deserialize::<MeasuredValue>(Some(headers))?;
+        }
+        data.row = record.position().map_or(0, |p| p.line());
+        for (header, value) in headers.iter().zip(record.iter()) {
+            if !Self::COLUMNS.contains(&header) {
+                data.factors.insert(header.to_owned(), value.to_owned());
+            }
         }
+        Ok(data)
     }
 }
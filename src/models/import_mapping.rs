@@ -0,0 +1,556 @@
+use crate::models::gage_data::GageData;
+use csv::{Reader, StringRecord};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// How the measurements are laid out in a file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImportLayout {
+    /// One measurement per row, with part, operator, trial and value columns.
+    #[default]
+    Long,
+    /// One row per part and a column per operator and trial, as in a
+    /// spreadsheet grid.
+    Wide,
+}
+
+impl ImportLayout {
+    pub const ALL: [ImportLayout; 2] = [ImportLayout::Long, ImportLayout::Wide];
+}
+
+impl fmt::Display for ImportLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportLayout::Long => write!(f, "One measurement per row"),
+            ImportLayout::Wide => write!(f, "One part per row"),
+        }
+    }
+}
+
+impl FromStr for ImportLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|l| l.to_string() == s)
+            .ok_or_else(|| format!("Unknown layout: {s}"))
+    }
+}
+
+/// A column of a long file that a header can be mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportField {
+    Part,
+    Operator,
+    Trial,
+    Value,
+}
+
+impl ImportField {
+    pub const ALL: [ImportField; 4] = [
+        ImportField::Part,
+        ImportField::Operator,
+        ImportField::Trial,
+        ImportField::Value,
+    ];
+
+    /// Lowercase headers this field is guessed from.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            ImportField::Part => &["part", "sample", "item", "unit"],
+            ImportField::Operator => &["operator", "appraiser", "op", "inspector"],
+            ImportField::Trial => &["trial", "replicate", "rep", "run", "repeat"],
+            ImportField::Value => &["value", "measurement", "result", "reading"],
+        }
+    }
+}
+
+impl fmt::Display for ImportField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportField::Part => write!(f, "Part"),
+            ImportField::Operator => write!(f, "Operator"),
+            ImportField::Trial => write!(f, "Trial"),
+            ImportField::Value => write!(f, "Value"),
+        }
+    }
+}
+
+/// A measurement column of a wide file and the operator and trial of its
+/// values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WideColumn {
+    pub column: usize,
+    pub operator: u32,
+    pub trial: u32,
+}
+
+/// Where the part, operator, trial and value of every measurement are found
+/// in a file that is not in the standard `Part,Operator,Trial,Value` format.
+/// Columns are indices into `headers`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImportMapping {
+    pub layout: ImportLayout,
+    pub headers: Vec<String>,
+    pub part: Option<usize>,
+    pub operator: Option<usize>,
+    /// Without a trial column, trials are numbered in order for every part
+    /// and operator
+    pub trial: Option<usize>,
+    pub value: Option<usize>,
+    /// Measurement columns of a wide file, in file order
+    pub measurements: Vec<WideColumn>,
+}
+
+/// Measurements read with a mapping, before they replace the loaded data.
+#[derive(Default, Clone, PartialEq)]
+pub struct ImportPreview {
+    pub items: Vec<GageData>,
+    /// Columns that are kept as extra factors
+    pub extra_columns: Vec<String>,
+    /// Values that were left out, and how operator names were numbered
+    pub notes: Vec<String>,
+}
+
+impl ImportMapping {
+    /// Guesses the layout and mapping of a file from its headers. Files with
+    /// at least two headers that name an operator and trial, such as
+    /// `Op1_T2`, are taken to be wide.
+    pub fn detect(headers: &StringRecord) -> Self {
+        let headers: Vec<String> = headers.iter().map(|h| h.to_owned()).collect();
+        let guess = |field: ImportField| {
+            headers
+                .iter()
+                .position(|h| field.aliases().contains(&h.trim().to_lowercase().as_str()))
+        };
+        let measurements: Vec<WideColumn> = headers
+            .iter()
+            .enumerate()
+            .filter_map(|(column, h)| {
+                Self::parse_column_name(h).map(|(operator, trial)| WideColumn {
+                    column,
+                    operator,
+                    trial,
+                })
+            })
+            .collect();
+        if measurements.len() >= 2 {
+            let part = guess(ImportField::Part).or_else(|| {
+                (0..headers.len()).find(|c| measurements.iter().all(|m| m.column != *c))
+            });
+            Self {
+                layout: ImportLayout::Wide,
+                part,
+                measurements,
+                headers,
+                ..Self::default()
+            }
+        } else {
+            Self {
+                layout: ImportLayout::Long,
+                part: guess(ImportField::Part),
+                operator: guess(ImportField::Operator),
+                trial: guess(ImportField::Trial),
+                value: guess(ImportField::Value),
+                headers,
+                ..Self::default()
+            }
+        }
+    }
+
+    /// Whether the headers are the ones `GageData` is read with, so that no
+    /// mapping is needed: part, operator and trial, and a value or, for
+    /// attribute studies, a rating.
+    pub fn is_standard(headers: &StringRecord) -> bool {
+        let has = |column: &str| headers.iter().any(|h| h == column);
+        ["Part", "Operator", "Trial"].into_iter().all(has) && (has("Value") || has("Rating"))
+    }
+
+    /// Operator and trial of a wide column from the numbers in its name:
+    /// `Op1_T2` and `Appraiser 1 Trial 2` are operator 1, trial 2. A single
+    /// number, as in `Trial 3`, is a trial of operator 1.
+    pub fn parse_column_name(header: &str) -> Option<(u32, u32)> {
+        let numbers: Vec<u32> = header
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().ok())
+            .collect::<Option<_>>()?;
+        match numbers.as_slice() {
+            [trial] => Some((1, *trial)),
+            [operator, trial] => Some((*operator, *trial)),
+            _ => None,
+        }
+    }
+
+    pub fn column(&self, field: ImportField) -> Option<usize> {
+        match field {
+            ImportField::Part => self.part,
+            ImportField::Operator => self.operator,
+            ImportField::Trial => self.trial,
+            ImportField::Value => self.value,
+        }
+    }
+
+    pub fn set_column(&mut self, field: ImportField, column: Option<usize>) {
+        match field {
+            ImportField::Part => self.part = column,
+            ImportField::Operator => self.operator = column,
+            ImportField::Trial => self.trial = column,
+            ImportField::Value => self.value = column,
+        }
+    }
+
+    /// Makes a column a measurement of an operator and trial, or not a
+    /// measurement column with `None`.
+    pub fn set_measurement(&mut self, column: usize, operator_trial: Option<(u32, u32)>) {
+        self.measurements.retain(|m| m.column != column);
+        if let Some((operator, trial)) = operator_trial {
+            let index = self.measurements.partition_point(|m| m.column < column);
+            self.measurements.insert(
+                index,
+                WideColumn {
+                    column,
+                    operator,
+                    trial,
+                },
+            );
+        }
+    }
+
+    /// What is still needed before the file can be read.
+    pub fn missing(&self) -> Option<String> {
+        let mut missing = Vec::new();
+        if self.part.is_none() {
+            missing.push("Part");
+        }
+        match self.layout {
+            ImportLayout::Long => {
+                if self.operator.is_none() {
+                    missing.push("Operator");
+                }
+                if self.value.is_none() {
+                    missing.push("Value");
+                }
+            }
+            ImportLayout::Wide => {
+                if self.measurements.is_empty() {
+                    missing.push("measurement columns");
+                }
+            }
+        }
+        (!missing.is_empty()).then(|| format!("Choose the {} column(s).", missing.join(", ")))
+    }
+
+    /// Reads the measurements of a file. Empty cells are skipped, and so are
+    /// values that are not numbers, with a note.
+    pub fn read(&self, data: &str) -> ImportPreview {
+        let mut preview = ImportPreview::default();
+        if self.missing().is_some() {
+            return preview;
+        }
+        let mut mapped: Vec<usize> = [self.part, self.operator, self.trial, self.value]
+            .into_iter()
+            .flatten()
+            .collect();
+        if self.layout == ImportLayout::Wide {
+            mapped = self.part.into_iter().collect();
+            mapped.extend(self.measurements.iter().map(|m| m.column));
+        }
+        let extra: Vec<usize> = (0..self.headers.len())
+            .filter(|c| !mapped.contains(c))
+            .collect();
+        preview.extra_columns = extra.iter().map(|c| self.headers[*c].clone()).collect();
+
+        let mut records = Vec::new();
+        let mut rdr = Reader::from_reader(data.as_bytes());
+        for result in rdr.records() {
+            match result {
+                Ok(record) => records.push(record),
+                Err(e) => preview.notes.push(e.to_string()),
+            }
+        }
+        // Operators that are names rather than numbers are numbered in order
+        // of appearance after the largest operator number, so the two cannot
+        // collide
+        let first_named = records
+            .iter()
+            .filter_map(|r| self.operator.and_then(|c| r.get(c)))
+            .filter_map(|o| o.trim().parse::<u32>().ok())
+            .max()
+            .unwrap_or_default();
+        let mut operators: HashMap<String, u32> = HashMap::new();
+        let mut named_operators: Vec<String> = Vec::new();
+        let mut trials: HashMap<(String, u32), u32> = HashMap::new();
+        for record in records.iter() {
+            let row = record.position().map_or(0, |p| p.line());
+            let field = |c: Option<usize>| c.and_then(|c| record.get(c)).unwrap_or_default().trim();
+            let factors: BTreeMap<String, String> = extra
+                .iter()
+                .map(|c| (self.headers[*c].clone(), field(Some(*c)).to_owned()))
+                .collect();
+            let part = field(self.part);
+            // Operator, trial and column of every measurement on the line.
+            // Without a trial column the trial is the next one of the part
+            // and operator, counted once the value is accepted.
+            let cells: Vec<(u32, Option<u32>, usize)> = match self.layout {
+                ImportLayout::Wide => self
+                    .measurements
+                    .iter()
+                    .map(|m| (m.operator, Some(m.trial), m.column))
+                    .collect(),
+                ImportLayout::Long => {
+                    let name = field(self.operator);
+                    let operator = name.parse().unwrap_or_else(|_| {
+                        *operators.entry(name.to_owned()).or_insert_with(|| {
+                            named_operators.push(name.to_owned());
+                            first_named + named_operators.len() as u32
+                        })
+                    });
+                    let trial = match self.trial {
+                        Some(column) => match field(Some(column)).parse() {
+                            Ok(trial) => Some(trial),
+                            Err(_) => {
+                                preview.notes.push(format!(
+                                    "Line {}, {}: \"{}\" is not a trial number",
+                                    row,
+                                    self.headers[column],
+                                    field(Some(column))
+                                ));
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    self.value
+                        .map(|value| (operator, trial, value))
+                        .into_iter()
+                        .collect()
+                }
+            };
+            for (operator, trial, column) in cells {
+                let cell = field(Some(column));
+                if cell.is_empty() {
+                    continue;
+                }
+                match cell.parse::<f64>() {
+                    Ok(value) => {
+                        let trial = trial.unwrap_or_else(|| {
+                            let count = trials.entry((part.to_owned(), operator)).or_default();
+                            *count += 1;
+                            *count
+                        });
+                        let mut data = GageData::new(part, operator, trial, value);
+                        data.factors = factors.clone();
+                        data.row = row;
+                        preview.items.push(data);
+                    }
+                    Err(_) => preview.notes.push(format!(
+                        "Line {}, {}: \"{}\" is not a number",
+                        row, self.headers[column], cell
+                    )),
+                }
+            }
+        }
+        if !named_operators.is_empty() {
+            let names: Vec<String> = named_operators
+                .iter()
+                .enumerate()
+                .map(|(i, n)| format!("{} = {}", n, first_named + i as u32 + 1))
+                .collect();
+            preview.notes.push(format!(
+                "Operators numbered in order of appearance: {}",
+                names.join(", ")
+            ));
+        }
+        preview
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(line: &str) -> StringRecord {
+        StringRecord::from(line.split(',').collect::<Vec<_>>())
+    }
+
+    /// Part, operator, trial and value of every item, to compare at a glance.
+    fn cells(preview: &ImportPreview) -> Vec<(String, u32, u32, f64)> {
+        preview
+            .items
+            .iter()
+            .map(|i| (i.part.clone(), i.operator, i.trial, i.value))
+            .collect()
+    }
+
+    #[test]
+    fn standard_headers_need_no_mapping() {
+        assert!(ImportMapping::is_standard(&headers(
+            "Part,Operator,Trial,Value"
+        )));
+        assert!(ImportMapping::is_standard(&headers(
+            "Part,Operator,Trial,Rating,Standard"
+        )));
+        assert!(ImportMapping::is_standard(&headers(
+            "Lot,Part,Operator,Trial,Value"
+        )));
+        assert!(!ImportMapping::is_standard(&headers("Part,Operator,Value")));
+        assert!(!ImportMapping::is_standard(&headers(
+            "part,operator,trial,value"
+        )));
+    }
+
+    #[test]
+    fn column_names_give_operator_and_trial() {
+        assert_eq!(ImportMapping::parse_column_name("Op1_T2"), Some((1, 2)));
+        assert_eq!(
+            ImportMapping::parse_column_name("Appraiser 3 Trial 1"),
+            Some((3, 1))
+        );
+        assert_eq!(ImportMapping::parse_column_name("Trial 3"), Some((1, 3)));
+        assert_eq!(ImportMapping::parse_column_name("Part"), None);
+        assert_eq!(ImportMapping::parse_column_name("A1 B2 C3"), None);
+    }
+
+    #[test]
+    fn long_file_is_detected_from_aliases() {
+        let mapping = ImportMapping::detect(&headers("Sample,Appraiser,Rep,Reading,Lot"));
+        assert_eq!(mapping.layout, ImportLayout::Long);
+        assert_eq!(mapping.part, Some(0));
+        assert_eq!(mapping.operator, Some(1));
+        assert_eq!(mapping.trial, Some(2));
+        assert_eq!(mapping.value, Some(3));
+        assert!(mapping.missing().is_none());
+    }
+
+    #[test]
+    fn wide_file_is_detected_from_operator_and_trial_columns() {
+        let mapping = ImportMapping::detect(&headers("Id,Op1_T1,Op1_T2,Op2_T1,Op2_T2"));
+        assert_eq!(mapping.layout, ImportLayout::Wide);
+        // Without a part header, the first column that is not a measurement
+        assert_eq!(mapping.part, Some(0));
+        let columns: Vec<(usize, u32, u32)> = mapping
+            .measurements
+            .iter()
+            .map(|m| (m.column, m.operator, m.trial))
+            .collect();
+        assert_eq!(columns, vec![(1, 1, 1), (2, 1, 2), (3, 2, 1), (4, 2, 2)]);
+    }
+
+    #[test]
+    fn missing_columns_are_named() {
+        let mut mapping = ImportMapping::detect(&headers("A,B,C"));
+        assert_eq!(
+            mapping.missing().as_deref(),
+            Some("Choose the Part, Operator, Value column(s).")
+        );
+        assert!(mapping.read("A,B,C\n1,2,3\n").items.is_empty());
+
+        mapping.set_column(ImportField::Part, Some(0));
+        mapping.set_column(ImportField::Operator, Some(1));
+        mapping.set_column(ImportField::Value, Some(2));
+        assert_eq!(mapping.column(ImportField::Value), Some(2));
+        assert!(mapping.missing().is_none());
+
+        mapping.layout = ImportLayout::Wide;
+        assert_eq!(
+            mapping.missing().as_deref(),
+            Some("Choose the measurement columns column(s).")
+        );
+    }
+
+    #[test]
+    fn measurements_are_kept_in_column_order() {
+        let mut mapping = ImportMapping::detect(&headers("Part,X,Y,Z"));
+        mapping.set_measurement(3, Some((2, 1)));
+        mapping.set_measurement(1, Some((1, 1)));
+        mapping.set_measurement(2, Some((1, 2)));
+        mapping.set_measurement(3, Some((2, 2)));
+        let columns: Vec<(usize, u32, u32)> = mapping
+            .measurements
+            .iter()
+            .map(|m| (m.column, m.operator, m.trial))
+            .collect();
+        assert_eq!(columns, vec![(1, 1, 1), (2, 1, 2), (3, 2, 2)]);
+
+        mapping.set_measurement(2, None);
+        assert_eq!(mapping.measurements.len(), 2);
+    }
+
+    #[test]
+    fn wide_file_is_read_a_measurement_per_cell() {
+        let data = "Part,Op1_T1,Op1_T2,Op2_T1,Op2_T2\nA,1.0,1.1,1.2,\nB,2.0,x,2.2,2.3\n";
+        let mapping = ImportMapping::detect(&headers("Part,Op1_T1,Op1_T2,Op2_T1,Op2_T2"));
+        let preview = mapping.read(data);
+        assert_eq!(
+            cells(&preview),
+            vec![
+                ("A".to_owned(), 1, 1, 1.0),
+                ("A".to_owned(), 1, 2, 1.1),
+                ("A".to_owned(), 2, 1, 1.2),
+                ("B".to_owned(), 1, 1, 2.0),
+                ("B".to_owned(), 2, 1, 2.2),
+                ("B".to_owned(), 2, 2, 2.3),
+            ]
+        );
+        assert_eq!(preview.items[3].row, 3);
+        assert!(preview.extra_columns.is_empty());
+        assert_eq!(preview.notes, vec!["Line 3, Op1_T2: \"x\" is not a number"]);
+    }
+
+    #[test]
+    fn long_file_numbers_trials_and_named_operators() {
+        let data = "Sample,Appraiser,Reading,Lot\n\
+                    A,2,1.0,L1\n\
+                    A,2,1.1,L1\n\
+                    A,Alice,1.2,L1\n\
+                    B,Bob,2.0,L2\n\
+                    B,Alice,2.1,L2\n\
+                    B,Alice,,L2\n";
+        let mapping = ImportMapping::detect(&headers("Sample,Appraiser,Reading,Lot"));
+        assert_eq!(mapping.trial, None);
+        let preview = mapping.read(data);
+        // Alice and Bob come after the largest operator number, 2
+        assert_eq!(
+            cells(&preview),
+            vec![
+                ("A".to_owned(), 2, 1, 1.0),
+                ("A".to_owned(), 2, 2, 1.1),
+                ("A".to_owned(), 3, 1, 1.2),
+                ("B".to_owned(), 4, 1, 2.0),
+                ("B".to_owned(), 3, 1, 2.1),
+            ]
+        );
+        assert_eq!(preview.extra_columns, vec!["Lot"]);
+        assert_eq!(
+            preview.items[3].factors.get("Lot").map(String::as_str),
+            Some("L2")
+        );
+        assert_eq!(
+            preview.notes,
+            vec!["Operators numbered in order of appearance: Alice = 3, Bob = 4"]
+        );
+    }
+
+    #[test]
+    fn unreadable_trial_is_noted_and_skipped() {
+        let data = "Part,Op,Run,Result\nA,1,1,0.5\nA,1,two,0.6\n";
+        let mapping = ImportMapping::detect(&headers("Part,Op,Run,Result"));
+        let preview = mapping.read(data);
+        assert_eq!(cells(&preview), vec![("A".to_owned(), 1, 1, 0.5)]);
+        assert_eq!(
+            preview.notes,
+            vec!["Line 3, Run: \"two\" is not a trial number"]
+        );
+    }
+
+    #[test]
+    fn layout_round_trips_through_its_name() {
+        for layout in ImportLayout::ALL {
+            assert_eq!(layout.to_string().parse::<ImportLayout>(), Ok(layout));
+        }
+        assert!("Sideways".parse::<ImportLayout>().is_err());
+    }
+}
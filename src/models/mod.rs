@@ -1,3 +1,3 @@
+pub mod data_quality;
 pub mod gage_data;
-pub mod gage_study_data;
-pub mod gage_study_data_set;
+pub mod import_mapping;